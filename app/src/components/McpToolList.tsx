@@ -1,5 +1,6 @@
 import { useEffect, useState } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { errorMessage } from '../lib/errors'
 import { Wrench, RefreshCw, AlertCircle } from 'lucide-react'
 
 interface ToolInfo {
@@ -22,7 +23,7 @@ export default function McpToolList() {
             setTools(res)
         } catch (e: any) {
             console.error('Failed to list tools', e)
-            setError(e.toString())
+            setError(errorMessage(e))
         } finally {
             setLoading(false)
         }
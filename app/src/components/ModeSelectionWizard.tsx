@@ -4,6 +4,7 @@ import { useSetupStore } from '../store/setupStore'
 import { useSettingsStore } from '../store/settingsStore'
 import type { ProviderConfig } from '../store/settingsStore'
 import { invoke } from '@tauri-apps/api/core'
+import { errorMessage } from '../lib/errors'
 
 type WizardStep = 'mode-select' | 'local-detection' | 'local-install' | 'local-service' | 'cloud-setup' | 'complete'
 
@@ -82,7 +83,7 @@ export const ModeSelectionWizard: React.FC = () => {
             setActiveProviderId(newProvider.id)
             setStep('complete')
         } catch (e) {
-            setError(`Failed to save provider: ${e}`)
+            setError(`Failed to save provider: ${errorMessage(e)}`)
         } finally {
             setIsSubmitting(false)
         }
@@ -162,12 +162,17 @@ function MonitoringDashboardInner() {
   }, [])
 
   useEffect(() => {
-    let intervalId: NodeJS.Timeout
-    if (isMonitoring) {
-      getRunningModels() // Initial fetch
-      intervalId = setInterval(getRunningModels, 2000)
+    if (!isMonitoring) return
+    getRunningModels() // Initial fetch
+    // Poll only while the window is focused; refresh right away when it regains focus
+    const intervalId = setInterval(() => {
+      if (document.hasFocus()) getRunningModels()
+    }, 2000)
+    window.addEventListener('focus', getRunningModels)
+    return () => {
+      clearInterval(intervalId)
+      window.removeEventListener('focus', getRunningModels)
     }
-    return () => clearInterval(intervalId)
   }, [isMonitoring])
 
 
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { Plus, Trash2, Check, Pencil, X, Zap, Bot, Brain, Sparkles, Plug, Globe } from 'lucide-react';
 import { useSettingsStore, type ProviderConfig } from '../store/settingsStore';
+import { errorMessage } from '../lib/errors';
 
 const PROVIDER_DEFAULTS: Record<string, { name: string; base_url: string; icon: typeof Bot }> = {
     ollama: { name: 'Ollama (Local)', base_url: 'http://localhost:11434', icon: Zap },
@@ -73,7 +74,7 @@ export default function ProviderSettings() {
             setNewBaseUrl('');
             setError(null);
         } catch (e) {
-            setError(`Failed to add provider: ${e}`);
+            setError(`Failed to add provider: ${errorMessage(e)}`);
         } finally {
             setLoading(false);
         }
@@ -86,7 +87,7 @@ export default function ProviderSettings() {
             setEditingProvider(null);
             setError(null);
         } catch (e) {
-            setError(`Failed to update provider: ${e}`);
+            setError(`Failed to update provider: ${errorMessage(e)}`);
         } finally {
             setLoading(false);
         }
@@ -105,7 +106,7 @@ export default function ProviderSettings() {
             await deleteProvider(id);
             setError(null);
         } catch (e) {
-            setError(`Failed to delete provider: ${e}`);
+            setError(`Failed to delete provider: ${errorMessage(e)}`);
         } finally {
             setLoading(false);
         }
@@ -120,7 +121,7 @@ export default function ProviderSettings() {
             setActiveProviderId(id);
             setError(null);
         } catch (e) {
-            setError(`Failed to set active provider: ${e}`);
+            setError(`Failed to set active provider: ${errorMessage(e)}`);
         }
     };
 
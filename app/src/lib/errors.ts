@@ -0,0 +1,28 @@
+// Commands reject with `{ kind, message }` (plus `status` for HTTP errors)
+export type OllieErrorKind =
+  | 'network_unreachable'
+  | 'timeout'
+  | 'http_status'
+  | 'not_found'
+  | 'parse'
+  | 'io'
+  | 'cancelled'
+  | 'read_only'
+  | 'other'
+
+export interface OllieError {
+  kind: OllieErrorKind
+  message: string
+  status?: number
+}
+
+export function isOllieError(e: unknown): e is OllieError {
+  return typeof e === 'object' && e !== null && 'kind' in e && 'message' in e
+}
+
+/** Display text for anything an `invoke` call can reject with */
+export function errorMessage(e: unknown): string {
+  if (isOllieError(e)) return e.message
+  if (e instanceof Error) return e.message
+  return String(e)
+}
@@ -35,10 +35,17 @@ export function useOllamaHealth(serverUrl?: string) {
   useEffect(() => {
     checkHealth()
     
-    // Check health every 30 seconds
-    const interval = setInterval(checkHealth, 30000)
+    // Check health every 30 seconds, but only while the window is focused;
+    // refresh right away when it regains focus
+    const interval = setInterval(() => {
+      if (document.hasFocus()) checkHealth()
+    }, 30000)
+    window.addEventListener('focus', checkHealth)
     
-    return () => clearInterval(interval)
+    return () => {
+      clearInterval(interval)
+      window.removeEventListener('focus', checkHealth)
+    }
   }, [serverUrl])
 
   return { health, isLoading, checkHealth }
@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { errorMessage } from '../lib/errors'
 import { listen } from '@tauri-apps/api/event'
 import { useSettingsStore } from './settingsStore'
 import { useModelsStore } from './modelsStore'
@@ -528,7 +529,7 @@ export const useChatStore = create<ChatState>((set, get) => ({
     } catch (error) {
       console.error('Failed to send message:', error)
       cleanupOnError()
-      state.updateMessage(assistantMessageId, `Error: ${error}`)
+      state.updateMessage(assistantMessageId, `Error: ${errorMessage(error)}`)
       cleanup()
     }
   },
@@ -762,7 +763,7 @@ export const useChatStore = create<ChatState>((set, get) => ({
 
     } catch (error) {
       get().setStreaming(false)
-      get().updateMessage(assistantMessageId, `Error: ${error}`)
+      get().updateMessage(assistantMessageId, `Error: ${errorMessage(error)}`)
       cleanup()
     }
   },
@@ -771,7 +772,7 @@ export const useChatStore = create<ChatState>((set, get) => ({
     const state = get()
     if (state.isStreaming) {
       try {
-        await invoke('chat_cancel')
+        await invoke('chat_cancel', { chatId: state.currentStreamId })
         state.setStreaming(false)
       } catch (error) {
         console.error('Failed to stop streaming:', error)
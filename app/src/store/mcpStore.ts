@@ -2,6 +2,7 @@ import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import type { McpServerConfig, McpServerStatus } from '../types/mcp';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../lib/errors';
 
 interface McpState {
     servers: McpServerConfig[];
@@ -71,7 +72,7 @@ export const useMcpStore = create<McpState>()(
                 } catch (e) {
                     console.error("Failed to connect MCP server:", e);
                     set((state) => ({
-                        serverStatuses: { ...state.serverStatuses, [id]: { id, status: 'error', error: String(e) } }
+                        serverStatuses: { ...state.serverStatuses, [id]: { id, status: 'error', error: errorMessage(e) } }
                     }));
                 }
             },
@@ -1,17 +1,21 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { errorMessage } from '../lib/errors'
 import { listen } from '@tauri-apps/api/event'
 
 export interface OllamaModel {
   name: string
   modified_at: string
   size: number
+  size_bytes: number
+  size_human: string
   digest: string
   details?: {
     format: string
     family: string
     families?: string[]
     parameter_size: string
+    parameter_count: number | null
     quantization_level: string
   }
 }
@@ -126,7 +130,7 @@ export const useModelsStore = create<ModelsState>((set) => ({
       }
       return pullId
     } catch (e) {
-      set({ error: String(e) })
+      set({ error: errorMessage(e) })
       return null
     }
   },
@@ -151,7 +155,7 @@ export const useModelsStore = create<ModelsState>((set) => ({
       await useModelsStore.getState().fetchModels()
       return true
     } catch (e) {
-      set({ error: String(e) })
+      set({ error: errorMessage(e) })
       return false
     }
   },
@@ -160,7 +164,7 @@ export const useModelsStore = create<ModelsState>((set) => ({
       const res = await invoke('model_show', { name }) as ModelInfo
       return res
     } catch (e) {
-      set({ error: String(e) })
+      set({ error: errorMessage(e) })
       return null
     }
   },
@@ -140,7 +140,7 @@ export const useSettingsStore = create<SettingsState>()(
           const server_url = `${s.serverUrl}:${s.serverPort}`
           const payload = {
             server_url,
-            default_model: s.defaultModel || undefined,
+            default_model: s.defaultModel || null,
             system_prompt: s.systemPrompt || undefined,
             default_params: {
               temperature: s.defaultParams.temperature,
@@ -240,7 +240,7 @@ export const useSettingsStore = create<SettingsState>()(
         const server_url = `${s.serverUrl}:${s.serverPort}`
         const payload = {
           server_url,
-          default_model: s.defaultModel || undefined,
+          default_model: s.defaultModel || null,
           system_prompt: s.systemPrompt || undefined,
           default_params: {
             temperature: s.defaultParams.temperature,
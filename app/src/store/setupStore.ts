@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { errorMessage } from '../lib/errors'
 
 export interface OllamaDetectionResult {
   installed: boolean
@@ -106,7 +107,7 @@ export const useSetupStore = create<SetupState>((set, get) => ({
     } catch (error) {
       const errorResult: ServiceActionResult = {
         success: false,
-        message: `Failed to start service: ${error}`,
+        message: `Failed to start service: ${errorMessage(error)}`,
         service_running: false
       }
       set({ 
@@ -141,7 +142,7 @@ export const useSetupStore = create<SetupState>((set, get) => ({
     } catch (error) {
       const errorResult: ServiceActionResult = {
         success: false,
-        message: `Failed to stop service: ${error}`,
+        message: `Failed to stop service: ${errorMessage(error)}`,
         service_running: true
       }
       set({ 
@@ -0,0 +1,69 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use crate::commands::events;
+
+/// Shared progress reporting for multi-item operations. Every batch emits
+/// `batch:progress` after each item and `batch:complete` once at the end, so the
+/// frontend renders all of them the same way.
+pub struct BatchProgress {
+    app: AppHandle,
+    batch_id: String,
+    kind: &'static str,
+    total: usize,
+    completed: usize,
+    failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BatchEvent<'a> {
+    batch_id: &'a str,
+    kind: &'a str,
+    total: usize,
+    completed: usize,
+    current: Option<&'a str>,
+    failed: usize,
+}
+
+impl BatchProgress {
+    /// Start a batch of `total` items and emit the initial zero-progress event
+    pub fn start(app: &AppHandle, batch_id: Option<String>, kind: &'static str, total: usize) -> Self {
+        let batch = Self {
+            app: app.clone(),
+            batch_id: batch_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            kind,
+            total,
+            completed: 0,
+            failed: 0,
+        };
+        batch.emit(events::BATCH_PROGRESS, None);
+        batch
+    }
+
+    pub fn id(&self) -> &str {
+        &self.batch_id
+    }
+
+    /// Record one finished item; `completed` counts failures too
+    pub fn item_done(&mut self, current: &str, ok: bool) {
+        self.completed += 1;
+        if !ok {
+            self.failed += 1;
+        }
+        self.emit(events::BATCH_PROGRESS, Some(current));
+    }
+
+    pub fn finish(self) {
+        self.emit(events::BATCH_COMPLETE, None);
+    }
+
+    fn emit(&self, event: &str, current: Option<&str>) {
+        let _ = self.app.emit(event, &BatchEvent {
+            batch_id: &self.batch_id,
+            kind: self.kind,
+            total: self.total,
+            completed: self.completed,
+            current,
+            failed: self.failed,
+        });
+    }
+}
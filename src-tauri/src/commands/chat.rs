@@ -2,16 +2,22 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use std::sync::atomic::{AtomicBool};
+use std::sync::atomic::{AtomicBool, Ordering};
+use futures::StreamExt;
+use tauri::{Emitter, State};
 use uuid::Uuid;
-use crate::commands::settings::{settings_get, provider_get_active};
-use crate::providers::{ProviderType, ChatMessage as ProviderChatMessage, ChatOptions as ProviderChatOptions};
-use crate::providers::traits::LLMProvider;
+use crate::commands::models::{CancellationMap, HttpClient, SimpleResponse};
+use crate::commands::settings::{get_ollama_url, max_response_chars, settings_get, provider_get_active, strip_patterns_for};
+use crate::commands::sys::send_with_failover;
+use crate::providers::{KeepAlive, ProviderConfig, ProviderType, ChatMessage as ProviderChatMessage, ChatOptions as ProviderChatOptions};
+use crate::providers::traits::{GenerationStats, LLMProvider};
 use crate::providers::orchestrator::ChatOrchestrator;
 use crate::providers::ollama::OllamaProvider;
 use crate::providers::openai::OpenAIProvider;
 use crate::providers::anthropic::AnthropicProvider;
 use crate::providers::google::GoogleProvider;
+use crate::commands::error::OllieError;
+use crate::commands::events;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
@@ -35,6 +41,43 @@ pub struct ChatOptions {
     pub top_k: Option<i32>,
     pub top_p: Option<f64>,
     pub max_tokens: Option<i32>,
+    pub repeat_penalty: Option<f64>,
+    pub repeat_last_n: Option<i32>,
+    pub presence_penalty: Option<f64>,
+    pub frequency_penalty: Option<f64>,
+    pub force_cpu: Option<bool>,
+    pub keep_alive: Option<KeepAlive>,
+}
+
+impl ChatOptions {
+    /// Reject repetition settings outside the ranges the backends accept
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(p) = self.repeat_penalty {
+            if !(0.0..=2.0).contains(&p) {
+                return Err(format!("repeat_penalty must be between 0.0 and 2.0, got {}", p));
+            }
+        }
+        if let Some(n) = self.repeat_last_n {
+            // -1 means "use the whole context window"
+            if n < -1 {
+                return Err(format!("repeat_last_n must be -1 or greater, got {}", n));
+            }
+        }
+        if let Some(p) = self.presence_penalty {
+            if !(-2.0..=2.0).contains(&p) {
+                return Err(format!("presence_penalty must be between -2.0 and 2.0, got {}", p));
+            }
+        }
+        if let Some(p) = self.frequency_penalty {
+            if !(-2.0..=2.0).contains(&p) {
+                return Err(format!("frequency_penalty must be between -2.0 and 2.0, got {}", p));
+            }
+        }
+        if let Some(keep_alive) = &self.keep_alive {
+            keep_alive.validate()?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,69 +91,50 @@ lazy_static::lazy_static! {
     static ref ACTIVE_STREAMS: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>> = Arc::new(Mutex::new(HashMap::new()));
 }
 
+/// Give `stream_id` its own cancel flag, refusing an id that is still running so one
+/// stream can never take over (or later remove) another's flag
+fn register_stream(streams: &mut HashMap<String, Arc<AtomicBool>>, stream_id: &str) -> Result<Arc<AtomicBool>, String> {
+    if streams.contains_key(stream_id) {
+        return Err(format!("Stream '{}' is already running", stream_id));
+    }
+    let flag = Arc::new(AtomicBool::new(false));
+    streams.insert(stream_id.to_string(), flag.clone());
+    Ok(flag)
+}
+
 #[tauri::command]
 pub async fn chat_stream(
     app: tauri::AppHandle,
     request: ChatRequest,
     _server_url: Option<String>, // Deprecated/Unused? ProviderConfig handles URL.
     provider_id: Option<String>,
-) -> Result<ChatResponse, String> {
+    stream_id: Option<String>, // Caller-chosen id so side-by-side panes can tell their events apart
+) -> Result<ChatResponse, OllieError> {
     
+    if let Some(opts) = &request.options {
+        opts.validate()?;
+    }
+
     // 1. Resolve Provider Configuration
-    let provider_config = if let Some(pid) = provider_id {
-        let settings = settings_get().await?;
-        settings.providers.into_iter()
-            .find(|p| p.id == pid)
-            .ok_or_else(|| format!("Provider '{}' not found", pid))?
-    } else {
-        provider_get_active().await?
-    };
+    let provider_config = resolve_provider(provider_id).await?;
 
     println!("Using provider: {} ({:?})", provider_config.name, provider_config.provider_type);
 
     // 2. Instantiate correct Provider Adapter
-    let provider: Box<dyn LLMProvider + Send + Sync> = match provider_config.provider_type {
-        ProviderType::Ollama => Box::new(OllamaProvider),
-        ProviderType::OpenAI | ProviderType::Other => Box::new(OpenAIProvider),
-        ProviderType::Anthropic => Box::new(AnthropicProvider),
-        ProviderType::Google => Box::new(GoogleProvider),
-    };
+    let provider = provider_for(&provider_config.provider_type);
 
     // 3. Register Stream for Cancellation
-    let stream_id = Uuid::new_v4().to_string();
-    let should_cancel = Arc::new(AtomicBool::new(false));
-    {
-        let mut active_streams = ACTIVE_STREAMS.lock().await;
-        active_streams.insert(stream_id.clone(), should_cancel.clone());
-    }
+    // Each stream owns its own entry, so concurrent chats never share a cancel flag
+    let stream_id = stream_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let should_cancel = register_stream(&mut *ACTIVE_STREAMS.lock().await, &stream_id)?;
 
     // 4. Transform Data Types (Command -> Provider)
-    let messages: Vec<ProviderChatMessage> = request.messages.iter().map(|m| {
-        ProviderChatMessage {
-            role: m.role.clone(),
-            content: m.content.clone(),
-            images: m.images.clone(),
-            tool_calls: m.tool_calls.clone(),
-            tool_call_id: None, // Frontend messages don't usually send IDs back unless it's a tool result?
-                                // If it's a tool result, m.role would be tool.
-                                // But `tool_call_id` is usually needed for `tool` messages.
-                                // Our `ChatMessage` struct in `chat.rs` doesn't have `tool_call_id`.
-                                // We might need to look into content or handle it if we want to support history with tool results correctly.
-                                // For now, let's assume `tool_calls` handles the assistant side, and we might be missing `tool_call_id` for tool output messages if frontend doesn't send it.
-                                // However, `orchestrator` handles new messages.
-                                // If `history` is passed from frontend, we trust it.
-        }
-    }).collect();
-
-    let options = request.options.map(|o| ProviderChatOptions {
-        temperature: o.temperature,
-        top_k: o.top_k,
-        top_p: o.top_p,
-        max_tokens: o.max_tokens,
-    });
+    let messages = to_provider_messages(&request.messages);
+    let options = with_default_keep_alive(request.options.map(to_provider_options)).await;
 
     // 5. Initialize Orchestrator
-    let orchestrator = ChatOrchestrator::new(app.clone(), provider);
+    let orchestrator = ChatOrchestrator::new(app.clone(), provider)
+        .with_max_response_chars(Some(max_response_chars().await));
 
     // 6. Run Conversation Loop
     let result = orchestrator.run_conversation(
@@ -127,6 +151,7 @@ pub async fn chat_stream(
         let mut active_streams = ACTIVE_STREAMS.lock().await;
         active_streams.remove(&stream_id);
     }
+    clear_graceful_stop(&stream_id);
 
     match result {
         Ok(_) => Ok(ChatResponse { success: true, error: None }),
@@ -137,12 +162,527 @@ pub async fn chat_stream(
     }
 }
 
+/// Stop a `chat_send` reply or a provider stream by id. The stream notices on its next
+/// chunk, emits `chat:cancelled` and removes its own entry. With `graceful` the reply
+/// runs on to the end of the current sentence, or for at most `GRACEFUL_STOP_WINDOW`.
 #[tauri::command]
-pub async fn chat_cancel(stream_id: String) -> Result<(), String> {
-    let active_streams = ACTIVE_STREAMS.lock().await;
-    if let Some(should_cancel) = active_streams.get(&stream_id) {
-        should_cancel.store(true, std::sync::atomic::Ordering::Relaxed);
-        println!("Cancelling stream {}", stream_id);
+pub async fn chat_cancel(
+    chat_id: String,
+    graceful: Option<bool>,
+    state: State<'_, CancellationMap>,
+) -> Result<SimpleResponse, OllieError> {
+    let flag = state.lock().unwrap().get(&chat_id).cloned();
+    let flag = match flag {
+        Some(flag) => Some(flag),
+        None => ACTIVE_STREAMS.lock().await.get(&chat_id).cloned(),
+    };
+    let Some(flag) = flag else {
+        return Ok(SimpleResponse { success: false, error: Some("Chat ID not found".to_string()) });
+    };
+    println!("Cancelling stream {}", chat_id);
+    if graceful == Some(true) {
+        GRACEFUL_STOPS.lock().unwrap().insert(chat_id);
+        // A stalled stream never reaches a sentence end, so hard-stop once the window passes
+        tokio::spawn(async move {
+            tokio::time::sleep(GRACEFUL_STOP_WINDOW).await;
+            flag.store(true, Ordering::Relaxed);
+        });
+    } else {
+        flag.store(true, Ordering::Relaxed);
+    }
+    Ok(SimpleResponse { success: true, error: None })
+}
+
+const GRACEFUL_STOP_WINDOW: std::time::Duration = std::time::Duration::from_secs(3);
+
+lazy_static::lazy_static! {
+    // Streams asked to stop at the next sentence boundary
+    static ref GRACEFUL_STOPS: std::sync::Mutex<std::collections::HashSet<String>> = std::sync::Mutex::new(std::collections::HashSet::new());
+}
+
+/// For a stream asked to stop gracefully, how many bytes of `token` to keep before stopping;
+/// None to keep streaming. A stream that is part of a `group` (a compare run) also stops when
+/// the group is asked to.
+pub fn graceful_cut(stream_id: &str, group: Option<&str>, token: &str) -> Option<usize> {
+    {
+        let stops = GRACEFUL_STOPS.lock().unwrap();
+        if !stops.contains(stream_id) && !group.is_some_and(|id| stops.contains(id)) {
+            return None;
+        }
+    }
+    // A sentence ends at terminal punctuation followed by whitespace (or the end of the
+    // token), or at a line break
+    let mut chars = token.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_break = match c {
+            '.' | '!' | '?' => chars.peek().map_or(true, |(_, next)| next.is_whitespace()),
+            '\n' => true,
+            _ => false,
+        };
+        if at_break {
+            return Some(i + c.len_utf8());
+        }
+    }
+    None
+}
+
+pub fn clear_graceful_stop(stream_id: &str) {
+    GRACEFUL_STOPS.lock().unwrap().remove(stream_id);
+}
+
+/// Talk to Ollama's `/api/chat` directly, without the provider layer or tools. Emits
+/// `chat:token` per partial message and `chat:done` with the timing stats; events carry `chat_id`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)] // Tauri commands take their state as parameters
+pub async fn chat_send(
+    app: tauri::AppHandle,
+    model: String,
+    messages: Vec<ChatMessage>,
+    chat_id: Option<String>,
+    server_url: Option<String>,
+    debug_echo: Option<bool>,
+    keep_alive: Option<KeepAlive>,
+    state: State<'_, CancellationMap>,
+    http: State<'_, HttpClient>,
+) -> Result<SimpleResponse, OllieError> {
+    let keep_alive = resolve_keep_alive(keep_alive).await?;
+    let mut images = Vec::with_capacity(messages.len());
+    for (i, m) in messages.iter().enumerate() {
+        let normalized = m.images.as_deref().map(normalize_images).transpose().map_err(|e| format!("Message {}: {}", i + 1, e))?;
+        images.push(normalized);
+    }
+    let pinned_url = server_url.clone();
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let endpoint = format!("{}/api/chat", url);
+    let chat_id = chat_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    // Everything that can fail goes before the cancel flag is registered, so an early
+    // return never leaves a stale entry behind
+    let strip = strip_patterns_for(&model).await?;
+    let max_chars = max_response_chars().await;
+    let client = http.client();
+    let mut payload = serde_json::json!({
+        "model": model,
+        "messages": messages.iter().zip(&images).map(|(m, images)| {
+            let mut msg = serde_json::json!({ "role": m.role, "content": m.content });
+            if let Some(images) = images {
+                msg["images"] = serde_json::json!(images);
+            }
+            msg
+        }).collect::<Vec<_>>(),
+        "stream": true,
+    });
+    if let Some(keep_alive) = keep_alive {
+        payload["keep_alive"] = serde_json::json!(keep_alive);
+    }
+
+    if debug_echo == Some(true) {
+        emit_request_debug(&app, "chat_id", &chat_id, &endpoint, &payload);
+    }
+
+    // Register cancellation token; a reused id would take over another pane's flag
+    let cancel_flag = register_stream(&mut state.lock().unwrap(), &chat_id)?;
+    // Until the first token arrives the model may still be loading into memory
+    let _ = app.emit(events::CHAT_LOADING, &serde_json::json!({ "chat_id": chat_id, "model": model }));
+    let result = match send_with_failover(pinned_url, |base| {
+        client.post(format!("{}/api/chat", base)).timeout(std::time::Duration::from_secs(60 * 60)).json(&payload)
+    }).await {
+        Ok(sent) => {
+            sent.report(&app, &url);
+            stream_chat_send(&app, sent.response, &chat_id, &model, &strip, max_chars, &cancel_flag).await
+        }
+        Err(e) => Err(e),
+    };
+
+    // Cleanup cancellation token
+    {
+        let mut map = state.lock().unwrap();
+        map.remove(&chat_id);
+    }
+    clear_graceful_stop(&chat_id);
+
+    if let Err(e) = result {
+        let _ = app.emit(events::CHAT_ERROR, &serde_json::json!({ "chat_id": chat_id, "error": e.message(), "kind": e.kind() }));
+        return Err(e);
+    }
+    Ok(SimpleResponse { success: true, error: None })
+}
+
+/// Emit `chat:request-debug` with the exact body about to be sent, credentials masked
+pub(crate) fn emit_request_debug(app: &tauri::AppHandle, id_key: &str, id: &str, endpoint: &str, body: &serde_json::Value) {
+    let mut body = body.clone();
+    redact_secrets(&mut body);
+    let mut event = serde_json::json!({ "endpoint": endpoint, "body": body });
+    event[id_key] = serde_json::json!(id);
+    let _ = app.emit(events::CHAT_REQUEST_DEBUG, &event);
+}
+
+fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                // Exact names only, so options like `max_tokens` stay visible
+                if ["authorization", "api_key", "apikey", "auth_token", "access_token", "token", "password", "secret"]
+                    .contains(&key.to_lowercase().as_str())
+                {
+                    *v = serde_json::json!("[redacted]");
+                } else {
+                    redact_secrets(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+// The reply so far, with its length in chars for the `max_response_chars` guard
+struct ChatReply {
+    content: String,
+    chars: usize,
+    max_chars: usize,
+}
+
+enum LineOutcome {
+    Continue,
+    /// A graceful stop reached its sentence boundary
+    GracefulStop,
+    /// The reply hit `max_response_chars`; `chat:done` has been sent
+    LengthGuard,
+}
+
+async fn stream_chat_send(
+    app: &tauri::AppHandle,
+    response: reqwest::Response,
+    chat_id: &str,
+    model: &str,
+    strip: &[regex::Regex],
+    max_chars: usize,
+    cancel_flag: &AtomicBool,
+) -> Result<(), OllieError> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let reason = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+        return Err(OllieError::from_status(status, match reason {
+            Some(reason) => format!("HTTP error: {}: {}", status, reason),
+            None => format!("HTTP error: {}", status),
+        }));
+    }
+
+    // Stream NDJSON the same way model_pull does
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+    let mut reply = ChatReply { content: String::new(), chars: 0, max_chars };
+    while let Some(chunk) = stream.next().await {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = app.emit(events::CHAT_CANCELLED, &serde_json::json!({ "chat_id": chat_id, "model": model }));
+            return Ok(());
+        }
+        let bytes = chunk?;
+        buffer.push_str(&String::from_utf8_lossy(&bytes));
+        while let Some(pos) = buffer.find('\n') {
+            let line = buffer[..pos].trim().to_string();
+            buffer = buffer[pos + 1..].to_string();
+            if line.is_empty() { continue; }
+            match handle_chat_line(app, &line, chat_id, model, strip, &mut reply)? {
+                LineOutcome::Continue => {}
+                LineOutcome::GracefulStop => {
+                    let _ = app.emit(events::CHAT_CANCELLED, &serde_json::json!({ "chat_id": chat_id, "model": model, "graceful": true }));
+                    return Ok(());
+                }
+                // Dropping the response aborts the request, so a looping model stops here
+                LineOutcome::LengthGuard => return Ok(()),
+            }
+        }
+    }
+    // Any trailing buffered line
+    let line = buffer.trim();
+    if !line.is_empty() {
+        handle_chat_line(app, line, chat_id, model, strip, &mut reply)?;
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+fn handle_chat_line(app: &tauri::AppHandle, line: &str, chat_id: &str, model: &str, strip: &[regex::Regex], reply: &mut ChatReply) -> Result<LineOutcome, String> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+        // One bad line shouldn't end the reply
+        let _ = app.emit(events::CHAT_PROGRESS, &serde_json::json!({
+            "chat_id": chat_id,
+            "progress": { "status": "parsing_error", "raw": line }
+        }));
+        return Ok(LineOutcome::Continue);
+    };
+    if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+        return Err(error.to_string());
+    }
+    if let Some(token) = value.pointer("/message/content").and_then(|c| c.as_str()).filter(|c| !c.is_empty()) {
+        let mut token = token;
+        let len = token.chars().count();
+        let over_limit = reply.chars + len > reply.max_chars;
+        if over_limit {
+            let keep = reply.max_chars - reply.chars;
+            token = &token[..token.char_indices().nth(keep).map_or(token.len(), |(i, _)| i)];
+        }
+        let cut = graceful_cut(chat_id, None, token);
+        let token = &token[..cut.unwrap_or(token.len())];
+        reply.content.push_str(token);
+        reply.chars += token.chars().count();
+        if !token.is_empty() {
+            let _ = app.emit(events::CHAT_TOKEN, &serde_json::json!({ "chat_id": chat_id, "model": model, "content": token }));
+        }
+        if cut.is_some() {
+            return Ok(LineOutcome::GracefulStop);
+        }
+        if over_limit {
+            emit_chat_done(app, chat_id, model, serde_json::json!("max_length_guard"), None, strip, &mut reply.content);
+            return Ok(LineOutcome::LengthGuard);
+        }
+    }
+    if value.get("done").and_then(|d| d.as_bool()) == Some(true) {
+        let stats = serde_json::from_value::<GenerationStats>(value.clone()).ok().map(GenerationStats::with_rate);
+        let done_reason = value.get("done_reason").cloned().unwrap_or_default();
+        emit_chat_done(app, chat_id, model, done_reason, stats, strip, &mut reply.content);
+    }
+    Ok(LineOutcome::Continue)
+}
+
+fn emit_chat_done(
+    app: &tauri::AppHandle,
+    chat_id: &str,
+    model: &str,
+    done_reason: serde_json::Value,
+    stats: Option<GenerationStats>,
+    strip: &[regex::Regex],
+    content: &mut String,
+) {
+    // Tokens went out as they came; the final content is the cleaned-up version
+    for pattern in strip {
+        *content = pattern.replace_all(content, "").into_owned();
+    }
+    let _ = app.emit(events::CHAT_DONE, &serde_json::json!({
+        "chat_id": chat_id,
+        "model": model,
+        "done_reason": done_reason,
+        "content": content,
+        "stats": stats,
+    }));
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompareResult {
+    pub model: String,
+    pub stream_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Stream the same conversation to several models concurrently for side-by-side answers.
+/// Each model runs as stream `{compare_id}:{model}` and all events carry `model`;
+/// `chat_cancel(compare_id)` stops every one of them.
+#[tauri::command]
+pub async fn chat_compare(
+    app: tauri::AppHandle,
+    models: Vec<String>,
+    messages: Vec<ChatMessage>,
+    options: Option<ChatOptions>,
+    server_url: Option<String>,
+    provider_id: Option<String>,
+    compare_id: Option<String>,
+) -> Result<Vec<CompareResult>, OllieError> {
+    if models.is_empty() {
+        return Err("At least one model is required".into());
+    }
+    if let Some(dup) = models.iter().enumerate().find(|(i, m)| models[..*i].contains(m)).map(|(_, m)| m) {
+        return Err(format!("Model '{}' is listed more than once", dup).into());
+    }
+    if let Some(opts) = &options {
+        opts.validate()?;
+    }
+
+    // An explicit server URL means "these models on that Ollama server"
+    let provider_config = match server_url {
+        Some(url) => ProviderConfig { base_url: Some(url), ..ProviderConfig::ollama_default() },
+        None => resolve_provider(provider_id).await?,
+    };
+
+    let compare_id = compare_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let should_cancel = register_stream(&mut *ACTIVE_STREAMS.lock().await, &compare_id)?;
+
+    let messages = to_provider_messages(&messages);
+    let options = with_default_keep_alive(options.map(to_provider_options)).await;
+
+    let max_chars = max_response_chars().await;
+    let runs = models.iter().map(|model| {
+        let stream_id = format!("{}:{}", compare_id, model);
+        let orchestrator = ChatOrchestrator::new(app.clone(), provider_for(&provider_config.provider_type))
+            .with_max_response_chars(Some(max_chars))
+            .with_stop_group(Some(compare_id.clone()));
+        let (config, messages, options, should_cancel) = (&provider_config, messages.clone(), options.clone(), should_cancel.clone());
+        async move {
+            let result = orchestrator
+                .run_conversation(config, model, messages, options, &stream_id, should_cancel)
+                .await;
+            CompareResult {
+                model: model.clone(),
+                stream_id,
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            }
+        }
+    });
+    let results = futures::future::join_all(runs).await;
+
+    {
+        let mut active_streams = ACTIVE_STREAMS.lock().await;
+        active_streams.remove(&compare_id);
+    }
+    clear_graceful_stop(&compare_id);
+
+    Ok(results)
+}
+
+async fn resolve_provider(provider_id: Option<String>) -> Result<ProviderConfig, String> {
+    if let Some(pid) = provider_id {
+        let settings = settings_get().await?;
+        settings.providers.into_iter()
+            .find(|p| p.id == pid)
+            .ok_or_else(|| format!("Provider '{}' not found", pid))
+    } else {
+        provider_get_active().await.map_err(String::from)
+    }
+}
+
+fn provider_for(provider_type: &ProviderType) -> Box<dyn LLMProvider + Send + Sync> {
+    match provider_type {
+        ProviderType::Ollama => Box::new(OllamaProvider),
+        ProviderType::OpenAI | ProviderType::Other => Box::new(OpenAIProvider),
+        ProviderType::Anthropic => Box::new(AnthropicProvider),
+        ProviderType::Google => Box::new(GoogleProvider),
+    }
+}
+
+fn to_provider_messages(messages: &[ChatMessage]) -> Vec<ProviderChatMessage> {
+    messages.iter().map(|m| {
+        ProviderChatMessage {
+            role: m.role.clone(),
+            content: m.content.clone(),
+            images: m.images.clone(),
+            tool_calls: m.tool_calls.clone(),
+            tool_call_id: None, // Frontend messages don't usually send IDs back unless it's a tool result?
+                                // If it's a tool result, m.role would be tool.
+                                // But `tool_call_id` is usually needed for `tool` messages.
+                                // Our `ChatMessage` struct in `chat.rs` doesn't have `tool_call_id`.
+                                // We might need to look into content or handle it if we want to support history with tool results correctly.
+                                // For now, let's assume `tool_calls` handles the assistant side, and we might be missing `tool_call_id` for tool output messages if frontend doesn't send it.
+                                // However, `orchestrator` handles new messages.
+                                // If `history` is passed from frontend, we trust it.
+        }
+    }).collect()
+}
+
+fn to_provider_options(o: ChatOptions) -> ProviderChatOptions {
+    ProviderChatOptions {
+        temperature: o.temperature,
+        top_k: o.top_k,
+        top_p: o.top_p,
+        max_tokens: o.max_tokens,
+        repeat_penalty: o.repeat_penalty,
+        repeat_last_n: o.repeat_last_n,
+        presence_penalty: o.presence_penalty,
+        frequency_penalty: o.frequency_penalty,
+        force_cpu: o.force_cpu,
+        keep_alive: o.keep_alive,
+    }
+}
+
+/// Images as Ollama wants them: bare base64. Takes raw base64 or a `data:` URL, and checks
+/// each decodes so a bad attachment fails here with a clear message.
+pub(crate) fn normalize_images(images: &[String]) -> Result<Vec<String>, String> {
+    use base64::Engine;
+    images
+        .iter()
+        .enumerate()
+        .map(|(i, image)| {
+            let image = image.trim();
+            let data = match image.strip_prefix("data:") {
+                Some(url) => match url.split_once(',') {
+                    Some((header, data)) if header.ends_with(";base64") => data,
+                    _ => return Err(format!("Image {} is a data URL without base64 content", i + 1)),
+                },
+                None => image,
+            };
+            let data: String = data.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+            if data.is_empty() {
+                return Err(format!("Image {} is empty", i + 1));
+            }
+            base64::engine::general_purpose::STANDARD
+                .decode(&data)
+                .map_err(|e| format!("Image {} is not valid base64: {}", i + 1, e))?;
+            Ok(data)
+        })
+        .collect()
+}
+
+/// Check a caller's keep_alive, falling back to `default_keep_alive` from settings; None
+/// leaves the field out so the server's own default applies
+pub(crate) async fn resolve_keep_alive(keep_alive: Option<KeepAlive>) -> Result<Option<KeepAlive>, String> {
+    if let Some(keep_alive) = &keep_alive {
+        keep_alive.validate()?;
+        return Ok(Some(keep_alive.clone()));
+    }
+    Ok(settings_get().await.ok().and_then(|s| s.default_keep_alive))
+}
+
+/// Fill in `default_keep_alive` from settings when the request didn't choose one
+async fn with_default_keep_alive(options: Option<ProviderChatOptions>) -> Option<ProviderChatOptions> {
+    let default = settings_get().await.ok().and_then(|s| s.default_keep_alive);
+    match (options, default) {
+        (Some(opts), _) if opts.keep_alive.is_some() => Some(opts),
+        (opts, Some(keep_alive)) => Some(ProviderChatOptions { keep_alive: Some(keep_alive), ..opts.unwrap_or_default() }),
+        (opts, None) => opts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelling_one_stream_leaves_the_other_running() {
+        let mut streams = HashMap::new();
+        let first = register_stream(&mut streams, "pane-1").unwrap();
+        let second = register_stream(&mut streams, "pane-2").unwrap();
+
+        // What `chat_cancel("pane-1")` does
+        streams["pane-1"].store(true, Ordering::Relaxed);
+        assert!(first.load(Ordering::Relaxed));
+        assert!(!second.load(Ordering::Relaxed));
+
+        // The cancelled stream cleans up after itself; the other keeps its entry
+        streams.remove("pane-1");
+        assert!(!second.load(Ordering::Relaxed));
+        assert!(Arc::ptr_eq(&streams["pane-2"], &second));
+    }
+
+    #[test]
+    fn a_running_stream_id_cannot_be_reused() {
+        let mut streams = HashMap::new();
+        let first = register_stream(&mut streams, "pane-1").unwrap();
+        assert!(register_stream(&mut streams, "pane-1").is_err());
+        assert!(Arc::ptr_eq(&streams["pane-1"], &first));
+    }
+
+    #[test]
+    fn graceful_stop_reaches_a_stream_only_through_its_own_id_or_group() {
+        GRACEFUL_STOPS.lock().unwrap().insert("team".to_string());
+        // An id that merely starts with "team:" is not part of that group
+        assert_eq!(graceful_cut("team:llama3", None, "Done. More"), None);
+        assert_eq!(graceful_cut("team:llama3", Some("team"), "Done. More"), Some(5));
+        assert_eq!(graceful_cut("team", None, "Done. More"), Some(5));
+        clear_graceful_stop("team");
+        assert_eq!(graceful_cut("team:llama3", Some("team"), "Done. More"), None);
+    }
+}
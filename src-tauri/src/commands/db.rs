@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::db::{get_pool, touch_chat_updated};
 use sqlx::FromRow;
+use std::collections::HashMap;
+use crate::commands::error::OllieError;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct ChatMeta {
@@ -25,7 +27,7 @@ pub struct MessageRow {
 }
 
 #[tauri::command]
-pub async fn db_create_chat(model: Option<String>, system_prompt: Option<String>, params_json: Option<String>) -> Result<ChatMeta, String> {
+pub async fn db_create_chat(model: Option<String>, system_prompt: Option<String>, params_json: Option<String>) -> Result<ChatMeta, OllieError> {
 	let pool = get_pool().await?;
 	let id = Uuid::new_v4().to_string();
 	let now = chrono::Utc::now().timestamp_millis();
@@ -45,10 +47,12 @@ pub async fn db_create_chat(model: Option<String>, system_prompt: Option<String>
 }
 
 #[tauri::command]
-pub async fn db_append_message(chat_id: String, role: String, content: String, meta_json: Option<String>) -> Result<MessageRow, String> {
+pub async fn db_append_message(chat_id: String, role: String, content: String, meta_json: Option<String>) -> Result<MessageRow, OllieError> {
 	let pool = get_pool().await?;
 	let id = Uuid::new_v4().to_string();
 	let now = chrono::Utc::now().timestamp_millis();
+	// Insert and timestamp bump commit together so an interrupted save leaves no half-written state
+	let mut tx = pool.begin().await.map_err(|e| format!("append message failed: {}", e))?;
 	sqlx::query("INSERT INTO messages (id, chat_id, role, content, created_at, meta_json) VALUES (?,?,?,?,?,?)")
 		.bind(&id)
 		.bind(&chat_id)
@@ -56,15 +60,16 @@ pub async fn db_append_message(chat_id: String, role: String, content: String, m
 		.bind(&content)
 		.bind(now)
 		.bind(&meta_json)
-		.execute(&pool)
+		.execute(&mut *tx)
 		.await
 		.map_err(|e| format!("append message failed: {}", e))?;
-	touch_chat_updated(&pool, &chat_id).await?;
+	touch_chat_updated(&mut tx, &chat_id).await?;
+	tx.commit().await.map_err(|e| format!("append message failed: {}", e))?;
 	Ok(MessageRow { id, chat_id, role, content, created_at: now, meta_json })
 }
 
 #[tauri::command]
-pub async fn db_set_chat_model(chat_id: String, model: String) -> Result<bool, String> {
+pub async fn db_set_chat_model(chat_id: String, model: String) -> Result<bool, OllieError> {
 	let pool = get_pool().await?;
 	let res = sqlx::query("UPDATE chats SET model = ? WHERE id = ?")
 		.bind(model)
@@ -76,7 +81,7 @@ pub async fn db_set_chat_model(chat_id: String, model: String) -> Result<bool, S
 }
 
 #[tauri::command]
-pub async fn db_set_chat_title(chat_id: String, title: String) -> Result<bool, String> {
+pub async fn db_set_chat_title(chat_id: String, title: String) -> Result<bool, OllieError> {
 	let pool = get_pool().await?;
 	let res = sqlx::query("UPDATE chats SET title = ? WHERE id = ?")
 		.bind(title)
@@ -88,7 +93,7 @@ pub async fn db_set_chat_title(chat_id: String, title: String) -> Result<bool, S
 }
 
 #[tauri::command]
-pub async fn db_list_chats(limit: Option<i64>) -> Result<Vec<ChatMeta>, String> {
+pub async fn db_list_chats(limit: Option<i64>) -> Result<Vec<ChatMeta>, OllieError> {
 	let pool = get_pool().await?;
 	let l = limit.unwrap_or(100);
 	let rows = sqlx::query_as::<_, ChatMeta>(
@@ -114,7 +119,7 @@ pub struct ChatWithFlags {
 }
 
 #[tauri::command]
-pub async fn db_list_chats_with_flags(limit: Option<i64>) -> Result<Vec<ChatWithFlags>, String> {
+pub async fn db_list_chats_with_flags(limit: Option<i64>) -> Result<Vec<ChatWithFlags>, OllieError> {
 	let pool = get_pool().await?;
 	let l = limit.unwrap_or(100);
 	let rows = sqlx::query_as::<_, ChatWithFlags>(
@@ -129,8 +134,133 @@ pub async fn db_list_chats_with_flags(limit: Option<i64>) -> Result<Vec<ChatWith
 	Ok(rows)
 }
 
+// Characters of the first user message shown in the sidebar
+const PREVIEW_CHARS: i64 = 120;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ConversationMeta {
+	#[sqlx(flatten)]
+	#[serde(flatten)]
+	pub chat: ChatMeta,
+	pub preview: Option<String>,
+	pub last_message_at: Option<i64>,
+	pub message_count: i64,
+}
+
+/// Chats with a short preview of the first user message; truncation happens in SQL so
+/// full message bodies never leave the database
+#[tauri::command]
+pub async fn conversation_list(limit: Option<i64>) -> Result<Vec<ConversationMeta>, OllieError> {
+	let pool = get_pool().await?;
+	let l = limit.unwrap_or(100);
+	let rows = sqlx::query_as::<_, ConversationMeta>(
+		r#"SELECT c.id, c.created_at, c.updated_at, c.model, c.system_prompt, c.params_json, c.title,
+		   (SELECT CASE WHEN length(m.content) > ?1 THEN substr(m.content, 1, ?1) || '…' ELSE m.content END
+		      FROM messages m WHERE m.chat_id = c.id AND m.role = 'user'
+		      ORDER BY m.created_at ASC LIMIT 1) AS preview,
+		   (SELECT MAX(m.created_at) FROM messages m WHERE m.chat_id = c.id) AS last_message_at,
+		   (SELECT COUNT(*) FROM messages m WHERE m.chat_id = c.id) AS message_count
+		   FROM chats c ORDER BY c.updated_at DESC LIMIT ?2"#
+	)
+	.bind(PREVIEW_CHARS)
+	.bind(l)
+	.fetch_all(&pool)
+	.await
+	.map_err(|e| format!("list conversations failed: {}", e))?;
+	Ok(rows)
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiMessage {
+	role: String,
+	#[serde(default)]
+	content: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpenAiExport {
+	Messages(Vec<OpenAiMessage>),
+	Request {
+		messages: Vec<OpenAiMessage>,
+		model: Option<String>,
+		title: Option<String>,
+	},
+}
+
+// Content is either a string or a list of parts; only text parts are kept
+fn openai_text(content: &serde_json::Value) -> String {
+	match content {
+		serde_json::Value::String(s) => s.clone(),
+		serde_json::Value::Array(parts) => parts
+			.iter()
+			.filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+			.collect::<Vec<_>>()
+			.join("\n"),
+		_ => String::new(),
+	}
+}
+
+/// Save an OpenAI-format conversation (a `messages` array, bare or inside a request body)
+/// as a new chat and return its id. System messages become the chat's system prompt.
+#[tauri::command]
+pub async fn conversation_import_openai(json: String) -> Result<String, OllieError> {
+	let (messages, model, title) = match serde_json::from_str::<OpenAiExport>(&json)
+		.map_err(|e| format!("Not an OpenAI messages export: {}", e))?
+	{
+		OpenAiExport::Messages(messages) => (messages, None, None),
+		OpenAiExport::Request { messages, model, title } => (messages, model, title),
+	};
+	let system_prompt = messages
+		.iter()
+		.filter(|m| m.role == "system" || m.role == "developer")
+		.map(|m| openai_text(&m.content))
+		.collect::<Vec<_>>()
+		.join("\n\n");
+	let turns: Vec<(String, String)> = messages
+		.iter()
+		.filter(|m| matches!(m.role.as_str(), "user" | "assistant" | "tool"))
+		.map(|m| (m.role.clone(), openai_text(&m.content)))
+		.filter(|(_, content)| !content.trim().is_empty())
+		.collect();
+	if turns.is_empty() {
+		return Err("The export has no user or assistant messages".into());
+	}
+
+	let pool = get_pool().await?;
+	let id = Uuid::new_v4().to_string();
+	let now = chrono::Utc::now().timestamp_millis();
+	let mut tx = pool.begin().await.map_err(|e| format!("import conversation failed: {}", e))?;
+	sqlx::query("INSERT INTO chats (id, created_at, updated_at, model, system_prompt, params_json, title) VALUES (?,?,?,?,?,?,?)")
+		.bind(&id)
+		.bind(now)
+		.bind(now)
+		.bind(&model)
+		.bind((!system_prompt.is_empty()).then_some(&system_prompt))
+		.bind(None::<String>)
+		.bind(&title)
+		.execute(&mut *tx)
+		.await
+		.map_err(|e| format!("import conversation failed: {}", e))?;
+	// Messages are ordered by created_at, so space them a millisecond apart
+	for (i, (role, content)) in turns.iter().enumerate() {
+		sqlx::query("INSERT INTO messages (id, chat_id, role, content, created_at, meta_json) VALUES (?,?,?,?,?,?)")
+			.bind(Uuid::new_v4().to_string())
+			.bind(&id)
+			.bind(role)
+			.bind(content)
+			.bind(now + i as i64)
+			.bind(None::<String>)
+			.execute(&mut *tx)
+			.await
+			.map_err(|e| format!("import conversation failed: {}", e))?;
+	}
+	tx.commit().await.map_err(|e| format!("import conversation failed: {}", e))?;
+	Ok(id)
+}
+
 #[tauri::command]
-pub async fn db_list_messages(chat_id: String, limit: Option<i64>) -> Result<Vec<MessageRow>, String> {
+pub async fn db_list_messages(chat_id: String, limit: Option<i64>) -> Result<Vec<MessageRow>, OllieError> {
 	let pool = get_pool().await?;
 	let l = limit.unwrap_or(500);
 	let rows = sqlx::query_as::<_, MessageRow>(
@@ -145,7 +275,7 @@ pub async fn db_list_messages(chat_id: String, limit: Option<i64>) -> Result<Vec
 }
 
 #[tauri::command]
-pub async fn db_delete_chat(chat_id: String) -> Result<bool, String> {
+pub async fn db_delete_chat(chat_id: String) -> Result<bool, OllieError> {
 	let pool = get_pool().await?;
 	let res = sqlx::query("DELETE FROM chats WHERE id = ?")
 		.bind(chat_id)
@@ -156,7 +286,7 @@ pub async fn db_delete_chat(chat_id: String) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub async fn db_update_message(id: String, content: String) -> Result<bool, String> {
+pub async fn db_update_message(id: String, content: String) -> Result<bool, OllieError> {
 	let pool = get_pool().await?;
 	let res = sqlx::query("UPDATE messages SET content = ? WHERE id = ?")
 		.bind(content)
@@ -168,7 +298,7 @@ pub async fn db_update_message(id: String, content: String) -> Result<bool, Stri
 }
 
 #[tauri::command]
-pub async fn db_delete_messages_after(chat_id: String, timestamp: i64) -> Result<u64, String> {
+pub async fn db_delete_messages_after(chat_id: String, timestamp: i64) -> Result<u64, OllieError> {
 	let pool = get_pool().await?;
 	let res = sqlx::query("DELETE FROM messages WHERE chat_id = ? AND created_at > ?")
 		.bind(chat_id)
@@ -177,4 +307,315 @@ pub async fn db_delete_messages_after(chat_id: String, timestamp: i64) -> Result
 		.await
 		.map_err(|e| format!("delete messages after failed: {}", e))?;
 	Ok(res.rows_affected())
-}
\ No newline at end of file
+}
+
+fn normalize_tag(tag: &str) -> Result<String, String> {
+	let tag = tag.trim();
+	if tag.is_empty() {
+		return Err("Tag cannot be empty".to_string());
+	}
+	Ok(tag.to_string())
+}
+
+#[tauri::command]
+pub async fn db_add_chat_tag(chat_id: String, tag: String) -> Result<bool, OllieError> {
+	let pool = get_pool().await?;
+	let tag = normalize_tag(&tag)?;
+	let res = sqlx::query("INSERT OR IGNORE INTO chat_tags (chat_id, tag) VALUES (?,?)")
+		.bind(chat_id)
+		.bind(tag)
+		.execute(&pool)
+		.await
+		.map_err(|e| format!("add chat tag failed: {}", e))?;
+	Ok(res.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn db_remove_chat_tag(chat_id: String, tag: String) -> Result<bool, OllieError> {
+	let pool = get_pool().await?;
+	let res = sqlx::query("DELETE FROM chat_tags WHERE chat_id = ? AND tag = ?")
+		.bind(chat_id)
+		.bind(tag.trim())
+		.execute(&pool)
+		.await
+		.map_err(|e| format!("remove chat tag failed: {}", e))?;
+	Ok(res.rows_affected() > 0)
+}
+
+#[tauri::command]
+pub async fn db_list_chat_tags(chat_id: String) -> Result<Vec<String>, OllieError> {
+	let pool = get_pool().await?;
+	let tags = sqlx::query_scalar::<_, String>("SELECT tag FROM chat_tags WHERE chat_id = ? ORDER BY tag")
+		.bind(chat_id)
+		.fetch_all(&pool)
+		.await
+		.map_err(|e| format!("list chat tags failed: {}", e))?;
+	Ok(tags)
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TaggedChat {
+	#[sqlx(flatten)]
+	#[serde(flatten)]
+	pub chat: ChatMeta,
+	#[sqlx(skip)]
+	pub tags: Vec<String>,
+	#[serde(skip)]
+	tags_joined: Option<String>,
+}
+
+// Tags are joined with the ASCII unit separator so commas inside tags survive
+const TAG_SEPARATOR: char = '\u{1f}';
+
+/// Find chats whose title or message content contains `query` and/or that carry `tag`;
+/// the 100 most recently updated matches
+#[tauri::command]
+pub async fn conversations_search(query: Option<String>, tag: Option<String>) -> Result<Vec<TaggedChat>, OllieError> {
+	Ok(search_chats(query, tag, 100).await?)
+}
+
+/// `conversations_search` with an explicit row limit; a negative limit returns every match
+pub async fn search_chats(query: Option<String>, tag: Option<String>, limit: i64) -> Result<Vec<TaggedChat>, String> {
+	let pool = get_pool().await?;
+	let pattern = query
+		.as_deref()
+		.map(str::trim)
+		.filter(|q| !q.is_empty())
+		.map(|q| format!("%{}%", q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+	let tag = tag.as_deref().map(str::trim).filter(|t| !t.is_empty()).map(str::to_string);
+	let mut rows = sqlx::query_as::<_, TaggedChat>(
+		r#"SELECT c.id, c.created_at, c.updated_at, c.model, c.system_prompt, c.params_json, c.title,
+		   (SELECT GROUP_CONCAT(t.tag, char(31)) FROM chat_tags t WHERE t.chat_id = c.id) AS tags_joined
+		   FROM chats c
+		   WHERE (?1 IS NULL
+		          OR c.title LIKE ?1 ESCAPE '\'
+		          OR EXISTS(SELECT 1 FROM messages m WHERE m.chat_id = c.id AND m.content LIKE ?1 ESCAPE '\'))
+		     AND (?2 IS NULL OR EXISTS(SELECT 1 FROM chat_tags t WHERE t.chat_id = c.id AND t.tag = ?2))
+		   ORDER BY c.updated_at DESC LIMIT ?3"#
+	)
+	.bind(pattern)
+	.bind(tag)
+	.bind(limit)
+	.fetch_all(&pool)
+	.await
+	.map_err(|e| format!("search chats failed: {}", e))?;
+	for row in &mut rows {
+		row.tags = row
+			.tags_joined
+			.take()
+			.map(|joined| joined.split(TAG_SEPARATOR).map(str::to_string).collect())
+			.unwrap_or_default();
+	}
+	Ok(rows)
+}
+
+/// Most recent chat activity per model, in epoch millis
+pub async fn model_last_used() -> Result<HashMap<String, i64>, String> {
+	let pool = get_pool().await?;
+	let rows = sqlx::query_as::<_, (String, i64)>(
+		"SELECT model, MAX(updated_at) FROM chats WHERE model IS NOT NULL GROUP BY model"
+	)
+	.fetch_all(&pool)
+	.await
+	.map_err(|e| format!("model last used failed: {}", e))?;
+	Ok(rows.into_iter().collect())
+}
+
+/// Upsert chat metadata and replace each chat's tags; message bodies are left untouched
+pub async fn restore_chat_meta(chats: &[TaggedChat]) -> Result<usize, String> {
+	let pool = get_pool().await?;
+	let mut tx = pool.begin().await.map_err(|e| format!("restore chats failed: {}", e))?;
+	for c in chats {
+		let chat = &c.chat;
+		sqlx::query(
+			r#"INSERT INTO chats (id, created_at, updated_at, model, system_prompt, params_json, title) VALUES (?,?,?,?,?,?,?)
+			   ON CONFLICT(id) DO UPDATE SET created_at = excluded.created_at, updated_at = excluded.updated_at,
+			   model = excluded.model, system_prompt = excluded.system_prompt, params_json = excluded.params_json, title = excluded.title"#
+		)
+		.bind(&chat.id)
+		.bind(chat.created_at)
+		.bind(chat.updated_at)
+		.bind(&chat.model)
+		.bind(&chat.system_prompt)
+		.bind(&chat.params_json)
+		.bind(&chat.title)
+		.execute(&mut *tx)
+		.await
+		.map_err(|e| format!("restore chats failed: {}", e))?;
+		sqlx::query("DELETE FROM chat_tags WHERE chat_id = ?")
+			.bind(&chat.id)
+			.execute(&mut *tx)
+			.await
+			.map_err(|e| format!("restore chats failed: {}", e))?;
+		for tag in &c.tags {
+			sqlx::query("INSERT OR IGNORE INTO chat_tags (chat_id, tag) VALUES (?,?)")
+				.bind(&chat.id)
+				.bind(tag)
+				.execute(&mut *tx)
+				.await
+				.map_err(|e| format!("restore chats failed: {}", e))?;
+		}
+	}
+	tx.commit().await.map_err(|e| format!("restore chats failed: {}", e))?;
+	Ok(chats.len())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+	Equal,
+	Insert,
+	Delete,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffSegment {
+	pub kind: DiffKind,
+	pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffReport {
+	pub id_a: String,
+	pub id_b: String,
+	pub text_a: String,
+	pub text_b: String,
+	/// Runs of words, in order; `Delete` is only in A, `Insert` only in B
+	pub segments: Vec<DiffSegment>,
+	pub additions: usize,
+	pub deletions: usize,
+}
+
+// Above this many LCS cells the differing middle is reported as one delete + one insert
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+fn push_segment(segments: &mut Vec<DiffSegment>, kind: DiffKind, word: &str) {
+	match segments.last_mut() {
+		Some(last) if last.kind == kind => {
+			last.text.push(' ');
+			last.text.push_str(word);
+		}
+		_ => segments.push(DiffSegment { kind, text: word.to_string() }),
+	}
+}
+
+/// Word-level diff via longest common subsequence, after trimming the shared prefix and suffix
+pub fn word_diff(a: &str, b: &str) -> Vec<DiffSegment> {
+	let a: Vec<&str> = a.split_whitespace().collect();
+	let b: Vec<&str> = b.split_whitespace().collect();
+	let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+	let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+	let (mid_a, mid_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+	let mut segments = Vec::new();
+	for w in &a[..prefix] {
+		push_segment(&mut segments, DiffKind::Equal, w);
+	}
+	if mid_a.len().saturating_mul(mid_b.len()) > MAX_DIFF_CELLS {
+		for w in mid_a {
+			push_segment(&mut segments, DiffKind::Delete, w);
+		}
+		for w in mid_b {
+			push_segment(&mut segments, DiffKind::Insert, w);
+		}
+	} else {
+		// lcs[i][j] = LCS length of mid_a[i..] and mid_b[j..]
+		let (n, m) = (mid_a.len(), mid_b.len());
+		let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+		for i in (0..n).rev() {
+			for j in (0..m).rev() {
+				lcs[i][j] = if mid_a[i] == mid_b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+			}
+		}
+		let (mut i, mut j) = (0, 0);
+		while i < n || j < m {
+			if i < n && j < m && mid_a[i] == mid_b[j] {
+				push_segment(&mut segments, DiffKind::Equal, mid_a[i]);
+				i += 1;
+				j += 1;
+			} else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+				push_segment(&mut segments, DiffKind::Delete, mid_a[i]);
+				i += 1;
+			} else {
+				push_segment(&mut segments, DiffKind::Insert, mid_b[j]);
+				j += 1;
+			}
+		}
+	}
+	for w in &a[a.len() - suffix..] {
+		push_segment(&mut segments, DiffKind::Equal, w);
+	}
+	segments
+}
+
+async fn last_assistant_message(chat_id: &str) -> Result<String, String> {
+	let pool = get_pool().await?;
+	sqlx::query_scalar::<_, String>(
+		"SELECT content FROM messages WHERE chat_id = ? AND role = 'assistant' ORDER BY created_at DESC LIMIT 1"
+	)
+	.bind(chat_id)
+	.fetch_optional(&pool)
+	.await
+	.map_err(|e| format!("diff conversations failed: {}", e))?
+	.ok_or_else(|| format!("Conversation {} has no assistant messages", chat_id))
+}
+
+/// Word-level diff of the last assistant reply in each conversation
+#[tauri::command]
+pub async fn conversations_diff(id_a: String, id_b: String) -> Result<DiffReport, OllieError> {
+	let text_a = last_assistant_message(&id_a).await?;
+	let text_b = last_assistant_message(&id_b).await?;
+	let segments = word_diff(&text_a, &text_b);
+	let count = |kind: DiffKind| segments.iter().filter(|s| s.kind == kind).map(|s| s.text.split(' ').count()).sum();
+	let additions = count(DiffKind::Insert);
+	let deletions = count(DiffKind::Delete);
+	Ok(DiffReport { id_a, id_b, text_a, text_b, segments, additions, deletions })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use DiffKind::{Delete, Equal, Insert};
+
+	fn diff(a: &str, b: &str) -> Vec<(DiffKind, String)> {
+		word_diff(a, b).into_iter().map(|s| (s.kind, s.text)).collect()
+	}
+
+	fn seg(kind: DiffKind, text: &str) -> (DiffKind, String) {
+		(kind, text.to_string())
+	}
+
+	#[test]
+	fn identical_texts_are_one_equal_run() {
+		assert_eq!(diff("the quick fox", "the  quick\nfox"), [seg(Equal, "the quick fox")]);
+		assert!(diff("", "").is_empty());
+	}
+
+	#[test]
+	fn pure_insertion() {
+		assert_eq!(diff("the fox", "the quick brown fox"), [seg(Equal, "the"), seg(Insert, "quick brown"), seg(Equal, "fox")]);
+		assert_eq!(diff("", "hello there"), [seg(Insert, "hello there")]);
+	}
+
+	#[test]
+	fn pure_deletion() {
+		assert_eq!(diff("the quick brown fox", "the fox"), [seg(Equal, "the"), seg(Delete, "quick brown"), seg(Equal, "fox")]);
+		assert_eq!(diff("hello there", ""), [seg(Delete, "hello there")]);
+	}
+
+	#[test]
+	fn change_in_the_middle_keeps_shared_prefix_and_suffix() {
+		assert_eq!(
+			diff("one two red green five six", "one two blue green five six"),
+			[seg(Equal, "one two"), seg(Delete, "red"), seg(Insert, "blue"), seg(Equal, "green five six")]
+		);
+	}
+
+	#[test]
+	fn oversized_middle_falls_back_to_delete_then_insert() {
+		let a: Vec<String> = (0..2001).map(|i| format!("a{}", i)).collect();
+		let b: Vec<String> = (0..2001).map(|i| format!("b{}", i)).collect();
+		let segments = diff(&format!("start {} end", a.join(" ")), &format!("start {} end", b.join(" ")));
+		assert_eq!(segments, [seg(Equal, "start"), seg(Delete, &a.join(" ")), seg(Insert, &b.join(" ")), seg(Equal, "end")]);
+	}
+}
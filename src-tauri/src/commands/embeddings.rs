@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::State;
+use crate::commands::models::{CancellationMap, HttpClient, SimpleResponse};
+use crate::commands::settings::get_ollama_url;
+use crate::commands::error::OllieError;
+
+/// Accepts either a single string or a batch of strings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EmbedInput {
+    Single(String),
+    Batch(Vec<String>),
+}
+
+impl EmbedInput {
+    fn texts(&self) -> Vec<&str> {
+        match self {
+            EmbedInput::Single(s) => vec![s.as_str()],
+            EmbedInput::Batch(v) => v.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// One vector per input, even for a single string
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmbeddingsResponse {
+    pub embeddings: Vec<Vec<f32>>,
+    /// Input tokens processed; not reported by the legacy endpoint
+    #[serde(default, alias = "prompt_eval_count")]
+    pub token_count: Option<u64>,
+}
+
+// Shape of the pre-0.2 `/api/embeddings` reply, which only embeds one prompt per request
+#[derive(Debug, Deserialize)]
+struct LegacyEmbedding {
+    embedding: Vec<f32>,
+}
+
+const EMBED_TIMEOUT_SECS: u64 = 60;
+
+#[tauri::command]
+pub async fn embeddings_create(
+    model: String,
+    input: EmbedInput,
+    embed_id: Option<String>,
+    server_url: Option<String>,
+    state: State<'_, CancellationMap>,
+    http: State<'_, HttpClient>,
+) -> Result<EmbeddingsResponse, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+
+    let embed_id = embed_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    // Register cancellation token
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut map = state.lock().unwrap();
+        map.insert(embed_id.clone(), cancel_flag.clone());
+    }
+
+    // A single request has no chunks to check between, so race it against the flag
+    let client = http.client();
+    let result = tokio::select! {
+        res = embed(&client, &url, &model, &input) => res,
+        _ = wait_for_cancel(&cancel_flag) => Err(OllieError::cancelled("Cancelled by user")),
+    };
+
+    // Cleanup cancellation token
+    {
+        let mut map = state.lock().unwrap();
+        map.remove(&embed_id);
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn embeddings_cancel(
+    embed_id: String,
+    state: State<'_, CancellationMap>,
+) -> Result<SimpleResponse, OllieError> {
+    let map = state.lock().unwrap();
+    if let Some(flag) = map.get(&embed_id) {
+        flag.store(true, Ordering::Relaxed);
+        Ok(SimpleResponse { success: true, error: None })
+    } else {
+        Ok(SimpleResponse { success: false, error: Some("Embedding ID not found".to_string()) })
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref DIMENSIONS: Mutex<HashMap<String, usize>> = Mutex::new(HashMap::new());
+}
+
+/// Length of the vectors `model` produces, probed once with a tiny input and cached
+#[tauri::command]
+pub async fn embedding_dimension(model: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<usize, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let key = format!("{}|{}", url, model);
+    if let Some(dim) = DIMENSIONS.lock().unwrap().get(&key) {
+        return Ok(*dim);
+    }
+
+    let resp = embed(&http.client(), &url, &model, &EmbedInput::Single("dimension probe".to_string())).await?;
+    let dim = resp
+        .embeddings
+        .first()
+        .map(|v| v.len())
+        .filter(|len| *len > 0)
+        .ok_or_else(|| format!("Model '{}' returned no embedding", model))?;
+    DIMENSIONS.lock().unwrap().insert(key, dim);
+    Ok(dim)
+}
+
+async fn embed(client: &reqwest::Client, url: &str, model: &str, input: &EmbedInput) -> Result<EmbeddingsResponse, OllieError> {
+    let endpoint = format!("{}/api/embed", url);
+    let resp = client
+        .post(&endpoint)
+        // Large batches can take a while on CPU
+        .timeout(std::time::Duration::from_secs(EMBED_TIMEOUT_SECS))
+        .json(&serde_json::json!({ "model": model, "input": input }))
+        .send()
+        .await?;
+    // Servers older than /api/embed only have the one-prompt-per-call endpoint. A newer
+    // server also answers 404 for a missing model, but with an Ollama `{"error": ...}` body
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        let body = resp.text().await.unwrap_or_default();
+        return match ollama_error_message(&body) {
+            Some(message) => Err(OllieError::not_found(message)),
+            None => embed_legacy(client, url, model, input).await,
+        };
+    }
+    if !resp.status().is_success() {
+        return Err(OllieError::from_status(resp.status(), format!("HTTP error: {}", resp.status())));
+    }
+    Ok(resp.json::<EmbeddingsResponse>().await?)
+}
+
+/// The `error` field of an Ollama error body, if that's what `body` is
+fn ollama_error_message(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value.get("error")?.as_str().map(str::to_string)
+}
+
+async fn embed_legacy(client: &reqwest::Client, url: &str, model: &str, input: &EmbedInput) -> Result<EmbeddingsResponse, OllieError> {
+    let endpoint = format!("{}/api/embeddings", url);
+    let mut embeddings = Vec::new();
+    for text in input.texts() {
+        let resp = client
+            .post(&endpoint)
+            .timeout(std::time::Duration::from_secs(EMBED_TIMEOUT_SECS))
+            .json(&serde_json::json!({ "model": model, "prompt": text }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(OllieError::from_status(resp.status(), format!("HTTP error: {}", resp.status())));
+        }
+        embeddings.push(resp.json::<LegacyEmbedding>().await?.embedding);
+    }
+    Ok(EmbeddingsResponse { embeddings, token_count: None })
+}
+
+async fn wait_for_cancel(flag: &AtomicBool) {
+    while !flag.load(Ordering::Relaxed) {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ollama_error_body_is_recognised() {
+        assert_eq!(
+            ollama_error_message(r#"{"error":"model \"nomic-embed-text\" not found, try pulling it first"}"#).as_deref(),
+            Some("model \"nomic-embed-text\" not found, try pulling it first")
+        );
+    }
+
+    #[test]
+    fn missing_route_body_is_not_an_ollama_error() {
+        assert_eq!(ollama_error_message("404 page not found"), None);
+        assert_eq!(ollama_error_message(""), None);
+        assert_eq!(ollama_error_message(r#"{"detail":"nope"}"#), None);
+    }
+}
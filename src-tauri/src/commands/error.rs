@@ -0,0 +1,130 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Error returned by every command. Serializes as `{ "kind": "...", "message": "..." }`
+/// (plus `status` for HTTP errors) so the frontend can branch on `kind` and show `message`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OllieError {
+    /// Nothing answered at the server address: Ollama isn't running or the URL is wrong
+    NetworkUnreachable { message: String },
+    Timeout { message: String },
+    HttpStatus { status: u16, message: String },
+    NotFound { message: String },
+    Parse { message: String },
+    Io { message: String },
+    Cancelled { message: String },
+    /// Refused because read-only (demo) mode is on
+    ReadOnly { message: String },
+    /// Anything not worth a variant of its own yet: validation failures, database errors, ...
+    Other { message: String },
+}
+
+impl OllieError {
+    pub fn message(&self) -> &str {
+        match self {
+            OllieError::NetworkUnreachable { message }
+            | OllieError::Timeout { message }
+            | OllieError::HttpStatus { message, .. }
+            | OllieError::NotFound { message }
+            | OllieError::Parse { message }
+            | OllieError::Io { message }
+            | OllieError::Cancelled { message }
+            | OllieError::ReadOnly { message }
+            | OllieError::Other { message } => message,
+        }
+    }
+
+    /// The serialized `kind` tag, for event payloads that carry the message separately
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OllieError::NetworkUnreachable { .. } => "network_unreachable",
+            OllieError::Timeout { .. } => "timeout",
+            OllieError::HttpStatus { .. } => "http_status",
+            OllieError::NotFound { .. } => "not_found",
+            OllieError::Parse { .. } => "parse",
+            OllieError::Io { .. } => "io",
+            OllieError::Cancelled { .. } => "cancelled",
+            OllieError::ReadOnly { .. } => "read_only",
+            OllieError::Other { .. } => "other",
+        }
+    }
+
+    /// A non-success response; 404 becomes `NotFound` since that's what callers act on
+    pub fn from_status(status: reqwest::StatusCode, message: impl Into<String>) -> Self {
+        let message = message.into();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            OllieError::NotFound { message }
+        } else {
+            OllieError::HttpStatus { status: status.as_u16(), message }
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        OllieError::NotFound { message: message.into() }
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        OllieError::Cancelled { message: message.into() }
+    }
+}
+
+impl fmt::Display for OllieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for OllieError {}
+
+impl From<reqwest::Error> for OllieError {
+    fn from(e: reqwest::Error) -> Self {
+        let message = e.to_string();
+        if e.is_timeout() {
+            OllieError::Timeout { message }
+        } else if e.is_connect() {
+            OllieError::NetworkUnreachable { message }
+        } else if e.is_decode() {
+            OllieError::Parse { message }
+        } else if let Some(status) = e.status() {
+            OllieError::from_status(status, message)
+        } else {
+            OllieError::Other { message }
+        }
+    }
+}
+
+impl From<std::io::Error> for OllieError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => OllieError::NotFound { message: e.to_string() },
+            std::io::ErrorKind::TimedOut => OllieError::Timeout { message: e.to_string() },
+            _ => OllieError::Io { message: e.to_string() },
+        }
+    }
+}
+
+impl From<serde_json::Error> for OllieError {
+    fn from(e: serde_json::Error) -> Self {
+        OllieError::Parse { message: e.to_string() }
+    }
+}
+
+// Internal helpers still report plain strings; they surface as `Other`
+impl From<String> for OllieError {
+    fn from(message: String) -> Self {
+        OllieError::Other { message }
+    }
+}
+
+impl From<&str> for OllieError {
+    fn from(message: &str) -> Self {
+        OllieError::Other { message: message.to_string() }
+    }
+}
+
+impl From<OllieError> for String {
+    fn from(e: OllieError) -> Self {
+        e.message().to_string()
+    }
+}
@@ -0,0 +1,257 @@
+use serde::Serialize;
+
+// Event names; emit through these so the catalog below and the emit sites stay in step
+pub const BATCH_PROGRESS: &str = "batch:progress";
+pub const BATCH_COMPLETE: &str = "batch:complete";
+pub const CHAT_STREAM_START: &str = "chat:stream-start";
+pub const CHAT_LOADING: &str = "chat:loading";
+pub const CHAT_CHUNK: &str = "chat:chunk";
+pub const CHAT_TOOL_START: &str = "chat:tool-start";
+pub const CHAT_COMPLETE: &str = "chat:complete";
+pub const CHAT_TOKEN: &str = "chat:token";
+pub const CHAT_DONE: &str = "chat:done";
+pub const CHAT_PROGRESS: &str = "chat:progress";
+pub const CHAT_ERROR: &str = "chat:error";
+pub const CHAT_CANCELLED: &str = "chat:cancelled";
+pub const CHAT_REQUEST_DEBUG: &str = "chat:request-debug";
+pub const GENERATE_TOKEN: &str = "generate:token";
+pub const GENERATE_DONE: &str = "generate:done";
+pub const GENERATE_PROGRESS: &str = "generate:progress";
+pub const GENERATE_ERROR: &str = "generate:error";
+pub const GENERATE_CANCELLED: &str = "generate:cancelled";
+pub const MODELS_PULL_QUEUED: &str = "models:pull-queued";
+pub const MODELS_PULL_START: &str = "models:pull-start";
+pub const MODELS_PULL_DUPLICATE: &str = "models:pull-duplicate";
+pub const MODELS_PULL_PROGRESS: &str = "models:pull-progress";
+pub const MODELS_PULL_COMPLETE: &str = "models:pull-complete";
+pub const MODELS_PULL_ERROR: &str = "models:pull-error";
+pub const MODELS_PULL_CANCELLED: &str = "models:pull-cancelled";
+pub const MODELS_PULL_PAUSED: &str = "models:pull-paused";
+pub const MODELS_PULL_RESUMED: &str = "models:pull-resumed";
+pub const MODELS_PULL_LOWDISK: &str = "models:pull-lowdisk";
+pub const MODELS_CREATE_PROGRESS: &str = "models:create-progress";
+pub const MODELS_CREATE_COMPLETE: &str = "models:create-complete";
+pub const MODELS_CREATE_ERROR: &str = "models:create-error";
+pub const MODELS_CREATE_CANCELLED: &str = "models:create-cancelled";
+pub const MONITORING_SYSTEM_METRICS: &str = "monitoring:system-metrics";
+pub const MONITORING_OLLAMA_STATUS: &str = "monitoring:ollama-status";
+pub const MONITORING_MODEL_METRICS: &str = "monitoring:model-metrics";
+pub const SERVER_CONNECTED: &str = "server:connected";
+pub const SERVER_DISCONNECTED: &str = "server:disconnected";
+pub const SERVER_FAILOVER: &str = "server:failover";
+pub const THEME_RESOLVED: &str = "theme:resolved";
+
+/// One field of an event payload; `ty` is the TypeScript-ish shape the frontend receives
+#[derive(Debug, Clone, Serialize)]
+pub struct EventField {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventDescriptor {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub payload: &'static [EventField],
+}
+
+const fn field(name: &'static str, ty: &'static str) -> EventField {
+    EventField { name, ty }
+}
+
+const PULL_ID: EventField = field("pull_id", "string");
+const CREATE_ID: EventField = field("create_id", "string");
+const CHAT_ID: EventField = field("chat_id", "string");
+const STREAM_ID: EventField = field("stream_id", "string");
+const GENERATE_ID: EventField = field("generate_id", "string");
+const MODEL: EventField = field("model", "string");
+const NAME: EventField = field("name", "string");
+const ERROR: EventField = field("error", "string");
+const PROGRESS: EventField = field("progress", "object (raw Ollama progress line, or { status: \"parsing_error\", raw })");
+const STATS: EventField = field("stats", "GenerationStats | null");
+
+const BATCH: &[EventField] = &[
+    field("batch_id", "string"),
+    field("kind", "\"delete\" | \"pull_manifest\""),
+    field("total", "number"),
+    field("completed", "number"),
+    field("current", "string | null"),
+    field("failed", "number"),
+];
+
+const CONNECTION: &[EventField] = &[
+    field("connected", "boolean"),
+    field("url", "string"),
+    field("error", "string | null"),
+    field("last_checked", "number (epoch ms)"),
+    field("last_changed", "number | null (epoch ms)"),
+];
+
+/// Every event the backend emits. A new event gets a name constant above and an entry here.
+pub const EVENTS: &[EventDescriptor] = &[
+    EventDescriptor { name: BATCH_PROGRESS, description: "A batch operation advanced by one item", payload: BATCH },
+    EventDescriptor { name: BATCH_COMPLETE, description: "A batch operation finished", payload: BATCH },
+    EventDescriptor { name: CHAT_STREAM_START, description: "A provider chat stream started", payload: &[STREAM_ID, MODEL] },
+    EventDescriptor {
+        name: CHAT_LOADING,
+        description: "Waiting for the first chunk while the model loads",
+        payload: &[field("stream_id | chat_id", "string"), MODEL],
+    },
+    EventDescriptor {
+        name: CHAT_CHUNK,
+        description: "A piece of a provider chat response",
+        payload: &[STREAM_ID, MODEL, field("message", "{ role, content }"), field("done", "boolean")],
+    },
+    EventDescriptor { name: CHAT_TOOL_START, description: "The model called a tool", payload: &[STREAM_ID, MODEL, field("tool", "string"), field("args", "object")] },
+    EventDescriptor {
+        name: CHAT_COMPLETE,
+        description: "A provider chat response finished",
+        payload: &[
+            STREAM_ID,
+            MODEL,
+            field("completed", "boolean"),
+            field("done_reason", "string"),
+            field("ran_on_cpu", "boolean | null"),
+            field("load_duration_ms", "number | null"),
+            field("message", "{ role, content, tool_calls }"),
+        ],
+    },
+    EventDescriptor { name: CHAT_TOKEN, description: "A token from chat_send", payload: &[CHAT_ID, MODEL, field("content", "string")] },
+    EventDescriptor {
+        name: CHAT_DONE,
+        description: "A chat stream finished; provider streams carry stream_id, chat_send carries chat_id and content",
+        payload: &[field("stream_id | chat_id", "string"), MODEL, field("done_reason", "string | null (\"max_length_guard\" when cut off)"), field("content", "string (chat_send only)"), STATS],
+    },
+    EventDescriptor { name: CHAT_PROGRESS, description: "A chat_send line that could not be parsed", payload: &[CHAT_ID, PROGRESS] },
+    EventDescriptor {
+        name: CHAT_ERROR,
+        description: "A chat stream failed",
+        payload: &[field("stream_id | chat_id", "string"), ERROR, field("kind", "OllieError kind (chat_send only)")],
+    },
+    EventDescriptor {
+        name: CHAT_CANCELLED,
+        description: "A chat stream stopped on request",
+        payload: &[field("stream_id | chat_id", "string"), MODEL, field("graceful", "boolean (only when stopped at a sentence boundary)")],
+    },
+    EventDescriptor {
+        name: CHAT_REQUEST_DEBUG,
+        description: "The request body about to be sent, with credentials masked (debug_echo)",
+        payload: &[field("chat_id | generate_id", "string"), field("endpoint", "string"), field("body", "object")],
+    },
+    EventDescriptor { name: GENERATE_TOKEN, description: "A token from generate", payload: &[GENERATE_ID, MODEL, field("content", "string")] },
+    EventDescriptor {
+        name: GENERATE_DONE,
+        description: "A generate stream finished",
+        payload: &[GENERATE_ID, MODEL, field("done_reason", "string | null"), field("context", "number[] | null"), STATS],
+    },
+    EventDescriptor { name: GENERATE_PROGRESS, description: "A generate line that could not be parsed", payload: &[GENERATE_ID, PROGRESS] },
+    EventDescriptor { name: GENERATE_ERROR, description: "A generate stream failed", payload: &[GENERATE_ID, ERROR] },
+    EventDescriptor { name: GENERATE_CANCELLED, description: "A generate stream stopped on request", payload: &[GENERATE_ID, MODEL] },
+    EventDescriptor { name: MODELS_PULL_QUEUED, description: "A pull is waiting for a free slot", payload: &[PULL_ID, NAME] },
+    EventDescriptor { name: MODELS_PULL_START, description: "A pull started downloading", payload: &[PULL_ID, NAME] },
+    EventDescriptor {
+        name: MODELS_PULL_DUPLICATE,
+        description: "A pull was refused because the same model is already being pulled; follow the existing one instead",
+        payload: &[PULL_ID, NAME, field("existing_pull_id", "string")],
+    },
+    EventDescriptor {
+        name: MODELS_PULL_PROGRESS,
+        description: "A progress line from Ollama's pull stream",
+        payload: &[
+            PULL_ID,
+            PROGRESS,
+            field("overall_percent", "number (0-100 across all layers; never goes back)"),
+            field("overall_bytes", "number (bytes downloaded across all layers)"),
+            field("overall_total", "number (bytes in the layers seen so far)"),
+            field("bytes_per_sec", "number (averaged over the last few seconds; 0 when stalled)"),
+            field("eta_seconds", "number | null (null while stalled or not yet measured)"),
+        ],
+    },
+    EventDescriptor { name: MODELS_PULL_COMPLETE, description: "A pull finished", payload: &[PULL_ID, field("phases", "PullPhaseTimings")] },
+    EventDescriptor { name: MODELS_PULL_ERROR, description: "A pull failed", payload: &[PULL_ID, ERROR, field("suggestions", "string[]")] },
+    EventDescriptor {
+        name: MODELS_PULL_CANCELLED,
+        description: "A pull stopped before finishing",
+        payload: &[PULL_ID, field("reason", "\"user\" | \"deadline_exceeded\" | \"disk_full\""), field("queued", "boolean (only when cancelled before starting)")],
+    },
+    EventDescriptor { name: MODELS_PULL_PAUSED, description: "A pull was paused and can be resumed", payload: &[PULL_ID, NAME] },
+    EventDescriptor { name: MODELS_PULL_RESUMED, description: "A paused pull started again", payload: &[PULL_ID, NAME] },
+    EventDescriptor {
+        name: MODELS_PULL_LOWDISK,
+        description: "Free space on the models disk fell below the threshold; the pull stops with disk_full",
+        payload: &[PULL_ID, NAME, field("free_bytes", "number"), field("threshold_bytes", "number")],
+    },
+    EventDescriptor { name: MODELS_CREATE_PROGRESS, description: "A progress line from Ollama's create stream", payload: &[CREATE_ID, PROGRESS] },
+    EventDescriptor { name: MODELS_CREATE_COMPLETE, description: "A model was created", payload: &[CREATE_ID, NAME] },
+    EventDescriptor { name: MODELS_CREATE_ERROR, description: "Creating a model failed", payload: &[CREATE_ID, ERROR] },
+    EventDescriptor { name: MODELS_CREATE_CANCELLED, description: "Creating a model was cancelled", payload: &[CREATE_ID, NAME] },
+    EventDescriptor {
+        name: MONITORING_SYSTEM_METRICS,
+        description: "Periodic CPU, memory, disk and network usage",
+        payload: &[
+            field("cpu_usage", "number"),
+            field("memory_usage", "number"),
+            field("memory_total", "number"),
+            field("disk_usage", "number"),
+            field("disk_total", "number"),
+            field("network_rx", "number"),
+            field("network_tx", "number"),
+            field("timestamp", "number"),
+        ],
+    },
+    EventDescriptor {
+        name: MONITORING_OLLAMA_STATUS,
+        description: "Periodic Ollama server status",
+        payload: &[
+            field("version", "string"),
+            field("uptime", "number"),
+            field("models_loaded", "string[]"),
+            field("active_streams", "number"),
+            field("queue_length", "number"),
+            field("server_health", "string"),
+            field("last_health_check", "number"),
+        ],
+    },
+    EventDescriptor {
+        name: MONITORING_MODEL_METRICS,
+        description: "Performance figures for one model",
+        payload: &[
+            field("model_name", "string"),
+            field("token_rate", "number"),
+            field("response_time", "number"),
+            field("memory_usage", "number"),
+            field("active_connections", "number"),
+            field("total_requests", "number"),
+            field("error_rate", "number"),
+            field("timestamp", "number"),
+        ],
+    },
+    EventDescriptor { name: SERVER_CONNECTED, description: "The Ollama server became reachable", payload: CONNECTION },
+    EventDescriptor { name: SERVER_DISCONNECTED, description: "The Ollama server stopped responding", payload: CONNECTION },
+    EventDescriptor {
+        name: SERVER_FAILOVER,
+        description: "The primary server was unreachable and the fallback served the request",
+        payload: &[field("primary", "string"), field("fallback", "string"), ERROR],
+    },
+    EventDescriptor { name: THEME_RESOLVED, description: "The effective light/dark theme", payload: &[field("theme", "string"), field("resolved", "\"light\" | \"dark\"")] },
+];
+
+/// Names and payload shapes of every event the backend emits
+#[tauri::command]
+pub fn events_catalog() -> Vec<EventDescriptor> {
+    EVENTS.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_event_is_listed_once() {
+        let mut names: Vec<&str> = EVENTS.iter().map(|e| e.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), EVENTS.len());
+    }
+}
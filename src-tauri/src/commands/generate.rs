@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use futures::StreamExt;
+use tauri::{AppHandle, Emitter, State};
+use crate::commands::chat::{emit_request_debug, normalize_images, resolve_keep_alive};
+use crate::commands::models::{CancellationMap, HttpClient};
+use crate::commands::settings::{get_ollama_url, settings_get, DefaultParams};
+use crate::commands::sys::send_with_failover;
+use crate::providers::traits::GenerationStats;
+use crate::providers::KeepAlive;
+use crate::commands::error::OllieError;
+use crate::commands::events;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    pub system: Option<String>,
+    pub params: Option<DefaultParams>,
+    /// `context` from an earlier response, to continue where it left off
+    pub context: Option<Vec<i64>>,
+    #[serde(default)]
+    pub stream: bool,
+    /// Emit `chat:request-debug` with the request body before sending it
+    #[serde(default)]
+    pub debug_echo: bool,
+    /// Base64 images (or `data:` URLs) for vision models such as llava
+    #[serde(default)]
+    pub images: Option<Vec<String>>,
+    /// How long the model stays loaded afterwards ("10m", -1 for always, 0 to unload)
+    #[serde(default)]
+    pub keep_alive: Option<KeepAlive>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GenerateResponse {
+    pub response: String,
+    /// Pass back as `context` to continue from this completion without resending it
+    pub context: Option<Vec<i64>>,
+    pub done_reason: Option<String>,
+    pub stats: Option<GenerationStats>,
+    /// Server that answered; differs from the configured one after a failover
+    pub served_by: Option<String>,
+}
+
+/// Single-shot completion against `/api/generate` with the default params from settings
+/// merged under `params`. With `stream` on, emits `generate:token` and `generate:done`
+/// (carrying `generate_id`); otherwise the whole response comes back at once.
+#[tauri::command]
+pub async fn generate(
+    app: AppHandle,
+    request: GenerateRequest,
+    generate_id: Option<String>,
+    server_url: Option<String>,
+    state: State<'_, CancellationMap>,
+    http: State<'_, HttpClient>,
+) -> Result<GenerateResponse, OllieError> {
+    let GenerateRequest { model, prompt, system, params, context, stream, debug_echo, images, keep_alive } = request;
+    let keep_alive = resolve_keep_alive(keep_alive).await?;
+    let images = images.as_deref().map(normalize_images).transpose()?;
+    let pinned_url = server_url.clone();
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let generate_id = generate_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let defaults = settings_get().await.ok().and_then(|s| s.default_params).unwrap_or_default();
+    let params = defaults.merge(&params.unwrap_or_default());
+    let mut options = serde_json::Map::new();
+    if let Some(t) = params.temperature {
+        options.insert("temperature".to_string(), serde_json::json!(t));
+    }
+    if let Some(k) = params.top_k {
+        options.insert("top_k".to_string(), serde_json::json!(k));
+    }
+    if let Some(p) = params.top_p {
+        options.insert("top_p".to_string(), serde_json::json!(p));
+    }
+    if let Some(m) = params.max_tokens {
+        options.insert("num_predict".to_string(), serde_json::json!(m));
+    }
+
+    let mut payload = serde_json::json!({
+        "model": model,
+        "prompt": prompt,
+        "stream": stream,
+        "options": options,
+    });
+    if let Some(system) = system {
+        payload["system"] = serde_json::json!(system);
+    }
+    if let Some(context) = context {
+        payload["context"] = serde_json::json!(context);
+    }
+    if let Some(images) = images {
+        payload["images"] = serde_json::json!(images);
+    }
+    if let Some(keep_alive) = keep_alive {
+        payload["keep_alive"] = serde_json::json!(keep_alive);
+    }
+
+    let endpoint = format!("{}/api/generate", url);
+    if debug_echo {
+        emit_request_debug(&app, "generate_id", &generate_id, &endpoint, &payload);
+    }
+
+    let client = http.client();
+    let sent = send_with_failover(pinned_url, |base| {
+        client.post(format!("{}/api/generate", base)).timeout(std::time::Duration::from_secs(60 * 60)).json(&payload)
+    }).await?;
+    sent.report(&app, &url);
+    let served_by = Some(sent.served_by);
+    let response = sent.response;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let reason = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+        return Err(match reason {
+            Some(reason) => OllieError::from_status(status, format!("HTTP error: {}: {}", status, reason)),
+            None => OllieError::from_status(status, format!("HTTP error: {}", status)),
+        });
+    }
+
+    if !stream {
+        let body: serde_json::Value = response.json().await.map_err(|e| format!("Failed to parse response: {}", e))?;
+        let mut result = GenerateResponse { served_by, ..Default::default() };
+        apply_line(&mut result, &body)?;
+        return Ok(result);
+    }
+
+    // Register cancellation token
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut map = state.lock().unwrap();
+        map.insert(generate_id.clone(), cancel_flag.clone());
+    }
+
+    let mut bytes_stream = response.bytes_stream();
+    let mut buffer = String::new();
+    let mut result = GenerateResponse { served_by, ..Default::default() };
+    let outcome = loop {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = app.emit(events::GENERATE_CANCELLED, &serde_json::json!({ "generate_id": generate_id, "model": model }));
+            break Ok(());
+        }
+        let Some(chunk) = bytes_stream.next().await else {
+            // Any trailing buffered line
+            let line = buffer.trim().to_string();
+            break if line.is_empty() { Ok(()) } else { handle_line(&app, &line, &generate_id, &model, &mut result) };
+        };
+        let bytes = match chunk {
+            Ok(bytes) => bytes,
+            Err(e) => break Err(e.to_string()),
+        };
+        buffer.push_str(&String::from_utf8_lossy(&bytes));
+        let mut failed = None;
+        while let Some(pos) = buffer.find('\n') {
+            let line = buffer[..pos].trim().to_string();
+            buffer = buffer[pos + 1..].to_string();
+            if line.is_empty() { continue; }
+            if let Err(e) = handle_line(&app, &line, &generate_id, &model, &mut result) {
+                failed = Some(e);
+                break;
+            }
+        }
+        if let Some(e) = failed {
+            break Err(e);
+        }
+    };
+
+    // Cleanup cancellation token
+    {
+        let mut map = state.lock().unwrap();
+        map.remove(&generate_id);
+    }
+
+    if let Err(e) = &outcome {
+        let _ = app.emit(events::GENERATE_ERROR, &serde_json::json!({ "generate_id": generate_id, "error": e }));
+    }
+    outcome.map(|_| result).map_err(OllieError::from)
+}
+
+fn handle_line(app: &AppHandle, line: &str, generate_id: &str, model: &str, result: &mut GenerateResponse) -> Result<(), String> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+        // One bad line shouldn't end the completion
+        let _ = app.emit(events::GENERATE_PROGRESS, &serde_json::json!({
+            "generate_id": generate_id,
+            "progress": { "status": "parsing_error", "raw": line }
+        }));
+        return Ok(());
+    };
+    let token = value.get("response").and_then(|r| r.as_str()).unwrap_or_default().to_string();
+    let done = apply_line(result, &value)?;
+    if !token.is_empty() {
+        let _ = app.emit(events::GENERATE_TOKEN, &serde_json::json!({ "generate_id": generate_id, "model": model, "content": token }));
+    }
+    if done {
+        let _ = app.emit(events::GENERATE_DONE, &serde_json::json!({
+            "generate_id": generate_id,
+            "model": model,
+            "done_reason": result.done_reason,
+            "context": result.context,
+            "stats": result.stats,
+        }));
+    }
+    Ok(())
+}
+
+// Fold one response object into the result; returns whether it was the final one
+fn apply_line(result: &mut GenerateResponse, value: &serde_json::Value) -> Result<bool, String> {
+    if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+        return Err(error.to_string());
+    }
+    if let Some(token) = value.get("response").and_then(|r| r.as_str()) {
+        result.response.push_str(token);
+    }
+    if value.get("done").and_then(|d| d.as_bool()) != Some(true) {
+        return Ok(false);
+    }
+    result.context = value.get("context").and_then(|c| serde_json::from_value(c.clone()).ok());
+    result.done_reason = value.get("done_reason").and_then(|r| r.as_str()).map(str::to_string);
+    result.stats = serde_json::from_value::<GenerationStats>(value.clone()).ok().map(GenerationStats::with_rate);
+    Ok(true)
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use crate::commands::models::{model_show, HttpClient};
+use crate::commands::error::OllieError;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelLicense {
+    pub text: String,
+    /// SPDX identifier (or a model-family license name) when the text is recognizable
+    pub spdx: Option<String>,
+}
+
+/// The license a model ships with, or None when its Modelfile declares none
+#[tauri::command]
+pub async fn model_license(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<Option<ModelLicense>, OllieError> {
+    let show = model_show(name, server_url, http).await?;
+    Ok(show
+        .license
+        .filter(|text| !text.trim().is_empty())
+        .map(|text| ModelLicense { spdx: detect_license(&text), text }))
+}
+
+// Checked in order, so more specific phrases come before the general ones they contain
+const KNOWN_LICENSES: &[(&[&str], &str)] = &[
+    (&["apache license", "version 2.0"], "Apache-2.0"),
+    (&["permission is hereby granted, free of charge"], "MIT"),
+    (&["mit license"], "MIT"),
+    (&["attribution-noncommercial-sharealike 4.0"], "CC-BY-NC-SA-4.0"),
+    (&["attribution-noncommercial 4.0"], "CC-BY-NC-4.0"),
+    (&["attribution-sharealike 4.0"], "CC-BY-SA-4.0"),
+    (&["creative commons attribution 4.0"], "CC-BY-4.0"),
+    (&["gnu affero general public license", "version 3"], "AGPL-3.0"),
+    (&["gnu general public license", "version 3"], "GPL-3.0"),
+    (&["bsd 3-clause"], "BSD-3-Clause"),
+    (&["openrail"], "OpenRAIL"),
+    (&["gemma terms of use"], "Gemma"),
+    (&["qwen research license"], "Qwen-Research"),
+    (&["qwen license agreement"], "Qwen"),
+    (&["tongyi qianwen license"], "Qwen"),
+    (&["deepseek license"], "DeepSeek"),
+    (&["mistral ai research license"], "MRL"),
+];
+
+fn detect_license(text: &str) -> Option<String> {
+    let lower = text.to_lowercase();
+
+    // Meta versions its community license per release: "Llama 3.1 Community License"
+    if let Some(pos) = lower.find(" community license") {
+        let before = &lower[..pos];
+        if let Some(start) = before.rfind("llama ") {
+            let version = before[start + "llama ".len()..].trim();
+            if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                return Some(format!("Llama-{}-Community", version));
+            }
+        }
+    }
+
+    KNOWN_LICENSES
+        .iter()
+        .find(|(needles, _)| needles.iter().all(|n| lower.contains(n)))
+        .map(|(_, id)| id.to_string())
+}
@@ -1,21 +1,22 @@
 use crate::mcp::McpClient;
+use crate::commands::error::OllieError;
 
 #[tauri::command]
-pub async fn connect_mcp_server(name: String, command: String, args: Vec<String>) -> Result<(), String> {
+pub async fn connect_mcp_server(name: String, command: String, args: Vec<String>) -> Result<(), OllieError> {
     match McpClient::connect(&name, &command, &args).await {
         Ok(_) => {
             println!("Connected to MCP server: {}", name);
             Ok(())
         },
-        Err(e) => Err(format!("Failed to connect to MCP server {}: {}", name, e)),
+        Err(e) => Err(format!("Failed to connect to MCP server {}: {}", name, e).into()),
     }
 }
 
 #[tauri::command]
-pub async fn connect_mcp_http(name: String, url: String, auth_token: Option<String>) -> Result<String, String> {
+pub async fn connect_mcp_http(name: String, url: String, auth_token: Option<String>) -> Result<String, OllieError> {
     match McpClient::connect_http(&name, &url, auth_token).await {
         Ok(_) => Ok(format!("Connected to {}", name)),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e.to_string().into()),
     }
 }
 
@@ -33,7 +34,7 @@ pub struct ToolInfo {
 }
 
 #[tauri::command]
-pub async fn list_tools() -> Result<Vec<ToolInfo>, String> {
+pub async fn list_tools() -> Result<Vec<ToolInfo>, OllieError> {
     let clients = McpClient::list_active_clients();
     let mut all_tools = Vec::new();
 
@@ -4,4 +4,15 @@ pub mod db;
 pub mod sys;
 pub mod settings;
 pub mod monitoring;
-pub mod mcp;
\ No newline at end of file
+pub mod mcp;
+pub mod embeddings;
+pub mod modelfile;
+pub mod registry;
+pub mod batch;
+pub mod snapshot;
+pub mod license;
+pub mod generate;
+pub mod secrets;
+pub mod events;
+pub mod error;
+pub mod util;
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use crate::commands::models::{model_show, HttpClient};
+use crate::commands::error::OllieError;
+
+/// Return a model's Modelfile without the comment header `ollama show` prepends
+#[tauri::command]
+pub async fn modelfile_of(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<String, OllieError> {
+    let show = model_show(name.clone(), server_url, http).await?;
+    let raw = show.modelfile.ok_or_else(|| format!("No Modelfile returned for '{}'", name))?;
+    Ok(modelfile_clean(raw))
+}
+
+/// Undo what `ollama show` does to a Modelfile: drop the generated header and point a
+/// `FROM` blob path back at the model reference the header names
+#[tauri::command]
+pub fn modelfile_clean(raw: String) -> String {
+    let source = generated_from(&raw);
+    let body = strip_generated_header(&raw);
+    let Some(source) = source else { return body };
+
+    let mut replaced = false;
+    let lines: Vec<String> = body
+        .lines()
+        .map(|line| {
+            if replaced {
+                return line.to_string();
+            }
+            match line.trim_start().split_once(char::is_whitespace) {
+                Some((keyword, arg)) if keyword.eq_ignore_ascii_case("FROM") && is_blob_path(arg.trim()) => {
+                    replaced = true;
+                    format!("FROM {}", source)
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+// The header suggests a replacement as `# FROM <model>`
+fn generated_from(raw: &str) -> Option<String> {
+    let mut lines = raw.lines();
+    if !lines.next().is_some_and(|l| l.starts_with("# Modelfile generated by")) {
+        return None;
+    }
+    lines
+        .take_while(|l| l.starts_with('#'))
+        .filter_map(|l| l.trim_start_matches('#').trim().strip_prefix("FROM "))
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty())
+}
+
+fn is_blob_path(arg: &str) -> bool {
+    arg.contains("sha256-") || arg.contains("sha256:") || arg.contains("/blobs/") || arg.contains("\\blobs\\")
+}
+
+// The generated header is a run of `#` lines at the very top, followed by a blank line
+fn strip_generated_header(raw: &str) -> String {
+    let mut lines = raw.lines().peekable();
+    if !lines.peek().is_some_and(|l| l.starts_with("# Modelfile generated by")) {
+        return raw.to_string();
+    }
+    while lines.peek().is_some_and(|l| l.starts_with('#')) {
+        lines.next();
+    }
+    while lines.peek().is_some_and(|l| l.trim().is_empty()) {
+        lines.next();
+    }
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelfileError {
+    /// 1-based line number; 0 for problems with the file as a whole
+    pub line: usize,
+    pub message: String,
+}
+
+const INSTRUCTIONS: &[&str] = &["FROM", "PARAMETER", "TEMPLATE", "SYSTEM", "ADAPTER", "LICENSE", "MESSAGE", "REQUIRES"];
+
+enum ParamKind {
+    Int,
+    Float,
+    Bool,
+    Text,
+}
+
+fn parameter_kind(name: &str) -> Option<ParamKind> {
+    Some(match name {
+        "num_ctx" | "num_predict" | "num_keep" | "num_batch" | "num_gpu" | "main_gpu" | "num_thread" | "repeat_last_n" | "seed"
+        | "top_k" | "mirostat" => ParamKind::Int,
+        "temperature" | "top_p" | "min_p" | "typical_p" | "tfs_z" | "repeat_penalty" | "presence_penalty" | "frequency_penalty"
+        | "mirostat_eta" | "mirostat_tau" => ParamKind::Float,
+        "penalize_newline" | "use_mmap" | "use_mlock" | "numa" | "low_vram" => ParamKind::Bool,
+        "stop" => ParamKind::Text,
+        _ => return None,
+    })
+}
+
+fn check_parameter(arg: &str) -> Option<String> {
+    let Some((name, value)) = arg.split_once(char::is_whitespace) else {
+        return Some(format!("PARAMETER needs a name and a value, got '{}'", arg));
+    };
+    let value = value.trim();
+    let Some(kind) = parameter_kind(&name.to_lowercase()) else {
+        return Some(format!("Unknown parameter '{}'", name));
+    };
+    let valid = match kind {
+        ParamKind::Int => value.parse::<i64>().is_ok(),
+        ParamKind::Float => value.parse::<f64>().is_ok_and(f64::is_finite),
+        ParamKind::Bool => value.parse::<bool>().is_ok(),
+        ParamKind::Text => !value.is_empty(),
+    };
+    if !valid {
+        let expected = match kind {
+            ParamKind::Int => "an integer",
+            ParamKind::Float => "a number",
+            ParamKind::Bool => "true or false",
+            ParamKind::Text => "a value",
+        };
+        return Some(format!("Parameter '{}' expects {}, got '{}'", name, expected, value));
+    }
+    if name.eq_ignore_ascii_case("mirostat") && !matches!(value, "0" | "1" | "2") {
+        return Some(format!("Parameter 'mirostat' must be 0, 1 or 2, got '{}'", value));
+    }
+    None
+}
+
+/// Check a Modelfile locally so mistakes surface before `/api/create` starts working on it
+#[tauri::command]
+pub fn modelfile_validate(content: String) -> Vec<ModelfileError> {
+    let mut errors = Vec::new();
+    let mut has_from = false;
+    let mut lines = content.lines().enumerate();
+
+    while let Some((i, line)) = lines.next() {
+        let line_no = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let (keyword, arg) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+        let arg = arg.trim();
+
+        // An odd number of """ opens a block that runs until the line holding the closing one
+        if arg.matches(r#"""""#).count() % 2 == 1 && !lines.any(|(_, l)| l.contains(r#"""""#)) {
+            errors.push(ModelfileError { line: line_no, message: r#"Unterminated """ block"#.to_string() });
+        }
+
+        let keyword = keyword.to_uppercase();
+        if !INSTRUCTIONS.contains(&keyword.as_str()) {
+            errors.push(ModelfileError { line: line_no, message: format!("Unknown instruction '{}'", keyword) });
+            continue;
+        }
+        if arg.is_empty() {
+            errors.push(ModelfileError { line: line_no, message: format!("{} needs an argument", keyword) });
+            continue;
+        }
+        let problem = match keyword.as_str() {
+            "FROM" => {
+                has_from = true;
+                None
+            }
+            "PARAMETER" => check_parameter(arg),
+            "MESSAGE" => {
+                let role = arg.split_whitespace().next().unwrap_or_default();
+                if !matches!(role.to_lowercase().as_str(), "system" | "user" | "assistant") {
+                    Some(format!("MESSAGE role must be system, user or assistant, got '{}'", role))
+                } else if arg.len() == role.len() {
+                    Some("MESSAGE needs content after the role".to_string())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        if let Some(message) = problem {
+            errors.push(ModelfileError { line: line_no, message });
+        }
+    }
+
+    if !has_from {
+        errors.push(ModelfileError { line: 0, message: "Missing FROM instruction".to_string() });
+    }
+    errors
+}
@@ -1,56 +1,570 @@
 use serde::{Deserialize, Serialize};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use futures_util::StreamExt;
-use crate::commands::settings::get_ollama_url;
+use crate::commands::settings::{get_ollama_url, max_concurrent_pulls, ollama_client_builder, settings_generation, show_concurrency, timeouts};
+use crate::commands::batch::BatchProgress;
+use crate::commands::db::model_last_used;
+use crate::commands::monitoring::{fetch_ps, is_same_model};
+use crate::commands::registry::{estimate_pull, fetch_tags, normalize_quant, probe_bandwidth, quant_from_tag, registry_client, remote_digest, search_library, suggest_names, LibraryModel, ModelRef, PullSizeEstimate};
+use crate::commands::sys::{available_space, ensure_writable, ollama_models_dir, send_with_failover};
+use crate::commands::error::OllieError;
+use crate::commands::util::{format_bytes, parse_parameter_count};
+use crate::commands::events;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "TagsDetails")]
 pub struct ModelDetails {
     pub format: String,
     pub family: String,
     pub families: Option<Vec<String>>,
     pub parameter_size: String,
+    /// `parameter_size` as a number, for sorting; None when it doesn't parse
+    pub parameter_count: Option<u64>,
     pub quantization_level: String,
 }
 
+#[derive(Deserialize)]
+struct TagsDetails {
+    format: String,
+    family: String,
+    families: Option<Vec<String>>,
+    parameter_size: String,
+    quantization_level: String,
+}
+
+impl From<TagsDetails> for ModelDetails {
+    fn from(details: TagsDetails) -> Self {
+        ModelDetails {
+            parameter_count: parse_parameter_count(&details.parameter_size),
+            format: details.format,
+            family: details.family,
+            families: details.families,
+            parameter_size: details.parameter_size,
+            quantization_level: details.quantization_level,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "TagsEntry")]
 pub struct OllamaModel {
     pub name: String,
     pub modified_at: String,
     pub size: i64,
+    /// Same as `size`, under a name that says what it is
+    pub size_bytes: i64,
+    /// `size` formatted for display, e.g. "4.1 GB"
+    pub size_human: String,
     pub digest: String,
     pub details: Option<ModelDetails>,
 }
 
+// A model as `/api/tags` lists it; the derived fields are filled in from it
+#[derive(Deserialize)]
+struct TagsEntry {
+    name: String,
+    modified_at: String,
+    size: i64,
+    digest: String,
+    details: Option<ModelDetails>,
+}
+
+impl From<TagsEntry> for OllamaModel {
+    fn from(entry: TagsEntry) -> Self {
+        OllamaModel {
+            name: entry.name,
+            modified_at: entry.modified_at,
+            size: entry.size,
+            size_bytes: entry.size,
+            size_human: format_bytes(entry.size),
+            digest: entry.digest,
+            details: entry.details,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModelsResponse {
     pub models: Vec<OllamaModel>,
+    /// Server that answered; differs from the configured one after a failover
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_by: Option<String>,
+}
+
+const DEFAULT_MODELS_LIST_RETRIES: u32 = 2;
+const MODELS_LIST_BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Installed models. Connection failures and timeouts are retried `retries` times (default 2)
+/// with doubling backoff, since a server that is still starting refuses connections briefly.
+#[tauri::command]
+pub async fn models_list(server_url: Option<String>, retries: Option<u32>, http: State<'_, HttpClient>) -> Result<ModelsResponse, OllieError> {
+    let retries = retries.unwrap_or(DEFAULT_MODELS_LIST_RETRIES);
+    let timeout = timeouts().await.list();
+    let mut attempt = 0;
+    let sent = loop {
+        let sent = send_with_failover(server_url.clone(), |url| {
+            http.client().get(format!("{}/api/tags", url)).timeout(timeout)
+        })
+        .await;
+        match sent {
+            Err(OllieError::NetworkUnreachable { .. } | OllieError::Timeout { .. }) if attempt < retries => {
+                tokio::time::sleep(MODELS_LIST_BACKOFF * 2u32.pow(attempt)).await;
+                attempt += 1;
+            }
+            sent => break sent?,
+        }
+    };
+
+    if sent.response.status().is_success() {
+        match sent.response.json::<ModelsResponse>().await {
+            Ok(models_response) => Ok(ModelsResponse { served_by: Some(sent.served_by), ..models_response }),
+            Err(e) => Err(format!("Failed to parse models response: {}", e).into()),
+        }
+    } else {
+        Err(OllieError::from_status(sent.response.status(), format!("Server returned status: {}", sent.response.status())))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FamilyStats {
+    pub count: usize,
+    pub total_size: i64,
+}
+
+/// Group installed models by `details.family` (models without details land under "unknown")
+#[tauri::command]
+pub async fn models_by_family(server_url: Option<String>, http: State<'_, HttpClient>) -> Result<HashMap<String, FamilyStats>, OllieError> {
+    let models = models_list(server_url, None, http.clone()).await?.models;
+    let mut families: HashMap<String, FamilyStats> = HashMap::new();
+    for model in models {
+        let family = model.details
+            .as_ref()
+            .map(|d| d.family.clone())
+            .filter(|f| !f.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+        let stats = families.entry(family).or_default();
+        stats.count += 1;
+        stats.total_size += model.size;
+    }
+    Ok(families)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecentModel {
+    #[serde(flatten)]
+    pub model: OllamaModel,
+    /// Last chat activity with this model (epoch millis), None if never used
+    pub last_used: Option<i64>,
 }
 
+/// Installed models ordered by when they were last chatted with; never-used models go last
 #[tauri::command]
-pub async fn models_list(server_url: Option<String>) -> Result<ModelsResponse, String> {
+pub async fn models_list_by_recency(server_url: Option<String>, http: State<'_, HttpClient>) -> Result<Vec<RecentModel>, OllieError> {
+    let models = models_list(server_url, None, http.clone()).await?.models;
+    let last_used = model_last_used().await.unwrap_or_default();
+
+    let mut recent: Vec<RecentModel> = models
+        .into_iter()
+        .map(|model| {
+            // Chats may record "llama3" for what the server lists as "llama3:latest"
+            let used = last_used.get(&model.name).copied().or_else(|| {
+                model.name.strip_suffix(":latest").and_then(|base| last_used.get(base).copied())
+            });
+            RecentModel { model, last_used: used }
+        })
+        .collect();
+    recent.sort_by(|a, b| b.last_used.cmp(&a.last_used).then_with(|| a.model.name.cmp(&b.model.name)));
+    Ok(recent)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub up_to_date: bool,
+    pub local_digest: String,
+    pub remote_digest: String,
+}
+
+/// Compare an installed model's digest with the registry's current manifest for its tag
+#[tauri::command]
+pub async fn model_update_available(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<UpdateInfo, OllieError> {
+    let model_ref = ModelRef::parse(&name)?;
+    let full_name = if name.contains(':') { name.clone() } else { format!("{}:latest", name) };
+    let local = models_list(server_url, None, http.clone())
+        .await?
+        .models
+        .into_iter()
+        .find(|m| m.name == full_name)
+        .ok_or_else(|| format!("Model '{}' is not installed", name))?;
+
+    let remote_digest = remote_digest(&registry_client()?, &model_ref).await?;
+    Ok(UpdateInfo {
+        up_to_date: local.digest == remote_digest,
+        local_digest: local.digest,
+        remote_digest,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuantEntry {
+    pub quantization: String,
+    pub installed: bool,
+    /// Local model names carrying this quantization
+    pub installed_as: Vec<String>,
+    /// Registry tags that resolve to this quantization
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuantMatrix {
+    pub base_name: String,
+    pub entries: Vec<QuantEntry>,
+    pub registry_error: Option<String>,
+}
+
+/// Quantizations of `base_name` installed locally versus published in the registry
+#[tauri::command]
+pub async fn model_quant_matrix(base_name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<QuantMatrix, OllieError> {
+    let base = base_name.split(':').next().unwrap_or(&base_name).to_string();
+    let mut entries: Vec<QuantEntry> = Vec::new();
+
+    for model in models_list(server_url, None, http.clone()).await?.models {
+        if model.name.split(':').next() != Some(base.as_str()) {
+            continue;
+        }
+        if let Some(details) = &model.details {
+            let e = quant_entry(&mut entries, &details.quantization_level);
+            e.installed = true;
+            e.installed_as.push(model.name.clone());
+        }
+    }
+
+    let registry_error = match fetch_tags(&registry_client()?, &ModelRef::parse(&base)?).await {
+        Ok(tags) => {
+            for tag in tags {
+                if let Some(quant) = quant_from_tag(&tag) {
+                    quant_entry(&mut entries, &quant).tags.push(tag);
+                }
+            }
+            None
+        }
+        Err(e) => Some(e),
+    };
+
+    entries.sort_by(|a, b| a.quantization.cmp(&b.quantization));
+    Ok(QuantMatrix { base_name: base, entries, registry_error })
+}
+
+fn quant_entry<'a>(entries: &'a mut Vec<QuantEntry>, quant: &str) -> &'a mut QuantEntry {
+    let quant = normalize_quant(quant);
+    if let Some(pos) = entries.iter().position(|e| e.quantization == quant) {
+        return &mut entries[pos];
+    }
+    entries.push(QuantEntry { quantization: quant, installed: false, installed_as: Vec::new(), tags: Vec::new() });
+    entries.last_mut().unwrap()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelWithStatus {
+    #[serde(flatten)]
+    pub model: OllamaModel,
+    pub loaded: bool,
+    /// Bytes resident in VRAM; only set while loaded
+    pub size_vram: Option<u64>,
+}
+
+/// Installed models tagged with whether they are currently loaded, loaded ones first
+#[tauri::command]
+pub async fn models_list_with_status(server_url: Option<String>, http: State<'_, HttpClient>) -> Result<Vec<ModelWithStatus>, OllieError> {
     let url = server_url.unwrap_or_else(get_ollama_url);
-    let endpoint = format!("{}/api/tags", url);
-    
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| e.to_string())?;
-    
-    match client.get(&endpoint).send().await {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.json::<ModelsResponse>().await {
-                    Ok(models_response) => Ok(models_response),
-                    Err(e) => Err(format!("Failed to parse models response: {}", e)),
+    let installed = models_list(Some(url.clone()), None, http.clone()).await?.models;
+    let running = fetch_ps(&http.client(), &url).await?.models;
+
+    let mut models: Vec<ModelWithStatus> = installed
+        .into_iter()
+        .map(|model| {
+            let size_vram = running.iter().find(|p| is_same_model(p, &model.name)).map(|p| p.size_vram);
+            ModelWithStatus { loaded: size_vram.is_some(), size_vram, model }
+        })
+        .collect();
+    // Stable sort keeps the server's ordering within each group
+    models.sort_by_key(|m| !m.loaded);
+    Ok(models)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EtaEstimate {
+    pub name: String,
+    pub remaining_bytes: u64,
+    /// Measured from this machine; a remote server may download faster or slower
+    pub bytes_per_sec: f64,
+    pub eta_secs: u64,
+}
+
+/// Rough pull duration: bytes still missing on disk over a short registry bandwidth probe
+#[tauri::command]
+pub async fn pull_eta_estimate(name: String, server_url: Option<String>) -> Result<EtaEstimate, OllieError> {
+    let client = registry_client()?;
+    let mut size = estimate_pull(&client, &name, &ollama_models_dir()).await?;
+    // Blobs of a remote server aren't visible here, so assume it has none
+    if !matches!(crate::commands::sys::server_locality(server_url), Ok(crate::commands::sys::Locality::Local)) {
+        size.remaining_bytes = size.total_bytes;
+    }
+    if size.remaining_bytes == 0 {
+        return Ok(EtaEstimate { name, remaining_bytes: 0, bytes_per_sec: 0.0, eta_secs: 0 });
+    }
+    let bytes_per_sec = probe_bandwidth(&client, &ModelRef::parse(&name)?).await?;
+    Ok(EtaEstimate {
+        name,
+        remaining_bytes: size.remaining_bytes,
+        bytes_per_sec,
+        eta_secs: (size.remaining_bytes as f64 / bytes_per_sec).ceil() as u64,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub model: LibraryModel,
+    /// Some tag of this model is already on the server
+    pub installed: bool,
+}
+
+/// Pullable models from the ollama.com library matching `query` (empty lists the most popular)
+#[tauri::command]
+pub async fn models_search(query: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<Vec<SearchResult>, OllieError> {
+    let client = registry_client()?;
+    let found = search_library(&client, &query).await;
+    // Searching still works while the server is down; nothing just shows as installed
+    let installed: Vec<String> = match models_list(server_url, Some(0), http).await {
+        Ok(list) => list.models.iter().map(|m| m.name.split(':').next().unwrap_or_default().to_string()).collect(),
+        Err(_) => Vec::new(),
+    };
+    Ok(found
+        .into_iter()
+        .map(|model| SearchResult { installed: installed.contains(&model.name), model })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VramEstimate {
+    pub name: String,
+    pub estimated_vram: u64,
+    /// "loaded" when measured from the running model, "file_size" when estimated from disk
+    pub source: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VramFitReport {
+    pub available_vram: u64,
+    pub used_vram: u64,
+    pub fits: Vec<VramEstimate>,
+    /// Models that would not fit alongside `fits` and would run partly on the CPU
+    pub spills: Vec<VramEstimate>,
+    /// Requested models that aren't installed, so have no size to go on
+    pub unknown: Vec<String>,
+}
+
+// Weights plus KV cache and compute buffers at the default context size
+const VRAM_OVERHEAD: f64 = 1.2;
+
+/// Which of `models` can stay resident together in `available_vram` bytes. Models are placed
+/// first-fit in the order given, so list the ones that matter most first.
+#[tauri::command]
+pub async fn vram_fit(models: Vec<String>, available_vram: u64, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<VramFitReport, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let installed = models_list(Some(url.clone()), None, http.clone()).await?.models;
+    // A loaded model's real footprint beats any estimate
+    let running = fetch_ps(&http.client(), &url).await.map(|ps| ps.models).unwrap_or_default();
+
+    let mut report = VramFitReport { available_vram, ..Default::default() };
+    for name in models {
+        let full_name = if name.contains(':') { name.clone() } else { format!("{}:latest", name) };
+        let estimate = if let Some(p) = running.iter().find(|p| is_same_model(p, &name)) {
+            VramEstimate { name, estimated_vram: p.size, source: "loaded".to_string() }
+        } else if let Some(m) = installed.iter().find(|m| m.name == full_name) {
+            let estimated_vram = (m.size.max(0) as f64 * VRAM_OVERHEAD) as u64;
+            VramEstimate { name, estimated_vram, source: "file_size".to_string() }
+        } else {
+            report.unknown.push(name);
+            continue;
+        };
+        if report.used_vram + estimate.estimated_vram <= available_vram {
+            report.used_vram += estimate.estimated_vram;
+            report.fits.push(estimate);
+        } else {
+            report.spills.push(estimate);
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DetailedModel {
+    #[serde(flatten)]
+    pub model: OllamaModel,
+    pub parameters: Option<serde_json::Value>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    pub context_length: Option<u64>,
+    /// Set when `/api/show` failed for this model; the other fields are then empty
+    pub error: Option<String>,
+}
+
+/// Installed models enriched with `/api/show` data, fetched `show_concurrency` at a time
+#[tauri::command]
+pub async fn models_list_detailed(server_url: Option<String>, http: State<'_, HttpClient>) -> Result<Vec<DetailedModel>, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let models = models_list(Some(url.clone()), None, http.clone()).await?.models;
+    let concurrency = show_concurrency().await;
+
+    // `buffered` keeps the server's ordering while still running requests in parallel
+    let detailed = futures_util::stream::iter(models.into_iter().map(|model| {
+        let (url, http) = (url.clone(), http.clone());
+        async move {
+            match model_show(model.name.clone(), Some(url), http).await {
+                Ok(show) => {
+                    let capabilities = show
+                        .extra
+                        .get("capabilities")
+                        .and_then(|c| serde_json::from_value(c.clone()).ok())
+                        .unwrap_or_default();
+                    // model_info keys are prefixed with the architecture, e.g. `llama.context_length`
+                    let context_length = show
+                        .extra
+                        .get("model_info")
+                        .and_then(|i| i.as_object())
+                        .and_then(|i| i.iter().find(|(k, _)| k.ends_with(".context_length")))
+                        .and_then(|(_, v)| v.as_u64());
+                    DetailedModel { model, parameters: show.parameters, capabilities, context_length, error: None }
                 }
-            } else {
-                Err(format!("Server returned status: {}", response.status()))
+                Err(e) => DetailedModel { model, parameters: None, capabilities: Vec::new(), context_length: None, error: Some(e.into()) },
+            }
+        }
+    }))
+    .buffered(concurrency)
+    .collect::<Vec<_>>()
+    .await;
+    Ok(detailed)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportRow {
+    name: String,
+    size: i64,
+    family: String,
+    quantization: String,
+    modified_at: String,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render the installed models as CSV or JSON, writing to `path` when given
+/// (returns the written path) or returning the rendered text otherwise
+#[tauri::command]
+pub async fn models_export(format: ExportFormat, path: Option<String>, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<String, OllieError> {
+    let rows: Vec<ExportRow> = models_list(server_url, None, http.clone())
+        .await?
+        .models
+        .into_iter()
+        .map(|m| {
+            let (family, quantization) = m
+                .details
+                .map(|d| (d.family, d.quantization_level))
+                .unwrap_or_default();
+            ExportRow { name: m.name, size: m.size, family, quantization, modified_at: m.modified_at }
+        })
+        .collect();
+
+    let rendered = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+        ExportFormat::Csv => {
+            let mut out = String::from("name,size,family,quantization,modified_at\n");
+            for r in &rows {
+                out.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    csv_field(&r.name),
+                    r.size,
+                    csv_field(&r.family),
+                    csv_field(&r.quantization),
+                    csv_field(&r.modified_at)
+                ));
             }
+            out
+        }
+    };
+
+    match path {
+        Some(path) => {
+            std::fs::write(&path, rendered).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+            Ok(path)
         }
-        Err(e) => Err(format!("Failed to fetch models: {}", e)),
+        None => Ok(rendered),
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiskProjection {
+    pub free_bytes: u64,
+    pub required_bytes: u64,
+    pub projected_free_bytes: i64,
+    pub insufficient: bool,
+    pub models: Vec<PullSizeEstimate>,
+    pub errors: Vec<String>,
+}
+
+/// Project free space on the models disk once `pending_models` have been pulled.
+/// Sizes come from registry manifests, minus blobs already on disk.
+#[tauri::command]
+pub async fn disk_projection(pending_models: Vec<String>, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<DiskProjection, OllieError> {
+    let installed: Vec<String> = match models_list(server_url, None, http.clone()).await {
+        Ok(resp) => resp.models.into_iter().map(|m| m.name).collect(),
+        Err(_) => Vec::new(),
+    };
+    let models_dir = ollama_models_dir();
+    let free_bytes = available_space(&models_dir).ok_or_else(|| format!("Cannot determine free space for {}", models_dir.display()))?;
+    let client = registry_client()?;
+
+    let mut models = Vec::new();
+    let mut errors = Vec::new();
+    for name in pending_models {
+        match estimate_pull(&client, &name, &models_dir).await {
+            Ok(mut estimate) => {
+                // Installed models need nothing new even if their blobs live elsewhere (remote server)
+                if installed.iter().any(|m| m == &name || *m == format!("{}:latest", name)) {
+                    estimate.present_bytes = estimate.total_bytes;
+                    estimate.remaining_bytes = 0;
+                }
+                models.push(estimate);
+            }
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+
+    let required_bytes: u64 = models.iter().map(|m| m.remaining_bytes).sum();
+    let projected_free_bytes = free_bytes as i64 - required_bytes as i64;
+    Ok(DiskProjection {
+        free_bytes,
+        required_bytes,
+        projected_free_bytes,
+        insufficient: projected_free_bytes < 0,
+        models,
+        errors,
+    })
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SimpleResponse {
     pub success: bool,
@@ -58,158 +572,803 @@ pub struct SimpleResponse {
 }
 
 #[tauri::command]
-pub async fn model_delete(name: String, server_url: Option<String>) -> Result<SimpleResponse, String> {
+pub async fn model_delete(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<SimpleResponse, OllieError> {
+    ensure_writable("model_delete")?;
     let url = server_url.unwrap_or_else(get_ollama_url);
-    let endpoint = format!("{}/api/delete", url);
+    let timeout = timeouts().await.delete();
+    Ok(delete_model(&http.client(), &url, &name, timeout).await)
+}
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(60))
-        .build()
-        .map_err(|e| e.to_string())?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchDeleteResult {
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Delete `names` one after another; a failure (e.g. a model that is loaded) is reported
+/// for that name and the rest still go ahead. Progress arrives as `batch:progress` with
+/// kind "delete".
+#[tauri::command]
+pub async fn models_delete_batch(
+    app: tauri::AppHandle,
+    names: Vec<String>,
+    server_url: Option<String>,
+    batch_id: Option<String>,
+    http: State<'_, HttpClient>,
+) -> Result<Vec<BatchDeleteResult>, OllieError> {
+    ensure_writable("models_delete_batch")?;
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let timeout = timeouts().await.delete();
+    let client = http.client();
+    let mut batch = BatchProgress::start(&app, batch_id, "delete", names.len());
+    let mut results = Vec::with_capacity(names.len());
+    for name in names {
+        let outcome = delete_model(&client, &url, &name, timeout).await;
+        batch.item_done(&name, outcome.success);
+        results.push(BatchDeleteResult { name, success: outcome.success, error: outcome.error });
+    }
+    batch.finish();
+    Ok(results)
+}
+
+async fn delete_model(client: &reqwest::Client, url: &str, name: &str, timeout: std::time::Duration) -> SimpleResponse {
+    let endpoint = format!("{}/api/delete", url);
 
     // Prefer DELETE with JSON body; if server rejects, fallback to POST
     let req_body = serde_json::json!({ "name": name });
     let resp = client
         .delete(&endpoint)
+        .timeout(timeout)
         .json(&req_body)
         .send()
         .await;
 
-    let resp = match resp {
-        Ok(r) if r.status().is_success() => r,
-        Ok(r) if r.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED => {
-            client.post(&endpoint).json(&req_body).send().await.map_err(|e| e.to_string())?
+    let resp = match resp {
+        Ok(r) if r.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED => {
+            client.post(&endpoint).timeout(timeout).json(&req_body).send().await
+        }
+        resp => resp,
+    };
+
+    match resp {
+        Ok(r) if r.status().is_success() => SimpleResponse { success: true, error: None },
+        Ok(r) => SimpleResponse { success: false, error: Some(format!("HTTP error: {}", r.status())) },
+        Err(e) => SimpleResponse { success: false, error: Some(format!("Request error: {}", e)) },
+    }
+}
+
+/// Evict `name` from memory right away by sending an empty prompt with `keep_alive: 0`.
+/// The model stays installed; this only frees the RAM/VRAM it was holding.
+#[tauri::command]
+pub async fn model_unload(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<SimpleResponse, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let body = serde_json::json!({ "model": name, "prompt": "", "keep_alive": 0, "stream": false });
+    let resp = http
+        .client()
+        .post(format!("{}/api/generate", url))
+        .timeout(std::time::Duration::from_secs(30))
+        .json(&body)
+        .send()
+        .await;
+
+    match resp {
+        Ok(r) if r.status().is_success() => Ok(SimpleResponse { success: true, error: None }),
+        Ok(r) => Ok(SimpleResponse { success: false, error: Some(format!("HTTP error: {}", r.status())) }),
+        Err(e) => Ok(SimpleResponse { success: false, error: Some(format!("Request error: {}", e)) }),
+    }
+}
+
+/// Duplicate `source` as `destination` without re-pulling. Ollama overwrites an existing
+/// `destination` rather than refusing, so this does too.
+#[tauri::command]
+pub async fn model_copy(source: String, destination: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<SimpleResponse, OllieError> {
+    ensure_writable("model_copy")?;
+    if destination.trim().is_empty() {
+        return Ok(SimpleResponse { success: false, error: Some("Destination name is empty".to_string()) });
+    }
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let resp = http
+        .client()
+        .post(format!("{}/api/copy", url))
+        .timeout(std::time::Duration::from_secs(60))
+        .json(&serde_json::json!({ "source": source, "destination": destination }))
+        .send()
+        .await;
+
+    match resp {
+        Ok(r) if r.status().is_success() => Ok(SimpleResponse { success: true, error: None }),
+        Ok(r) if r.status() == reqwest::StatusCode::NOT_FOUND => {
+            Ok(SimpleResponse { success: false, error: Some(format!("Model '{}' not found", source)) })
+        }
+        Ok(r) => Ok(SimpleResponse { success: false, error: Some(format!("HTTP error: {}", r.status())) }),
+        Err(e) => Ok(SimpleResponse { success: false, error: Some(format!("Request error: {}", e)) }),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShowResponse {
+    pub modelfile: Option<String>,
+    pub parameters: Option<serde_json::Value>,
+    pub template: Option<String>,
+    pub license: Option<String>,
+    #[serde(flatten)]
+    pub extra: std::collections::HashMap<String, serde_json::Value>,
+}
+
+#[tauri::command]
+pub async fn model_show(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<ShowResponse, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let endpoint = format!("{}/api/show", url);
+
+    // Use POST body per Ollama API examples
+    let resp = http
+        .client()
+        .post(&endpoint)
+        .timeout(timeouts().await.show())
+        .json(&serde_json::json!({ "name": name }))
+        .send()
+        .await
+        ?;
+
+    if !resp.status().is_success() {
+        return Err(OllieError::from_status(resp.status(), format!("HTTP error: {}", resp.status())));
+    }
+
+    resp.json::<ShowResponse>().await.map_err(OllieError::from)
+}
+
+/// List the variables a model's prompt template reads from its top-level data
+/// (e.g. System, Prompt, Messages, Tools), in order of first use
+#[tauri::command]
+pub async fn model_template_vars(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<Vec<String>, OllieError> {
+    let show = model_show(name, server_url, http).await?;
+    Ok(show.template.as_deref().map(template_vars).unwrap_or_default())
+}
+
+// Walks the Go template actions, tracking `range`/`with` blocks (which rebind `.`)
+// so that fields like `.Role` inside `range .Messages` aren't reported as top-level
+fn template_vars(template: &str) -> Vec<String> {
+    let mut vars: Vec<String> = Vec::new();
+    let mut blocks: Vec<bool> = Vec::new(); // true when the block rebinds dot
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let action = after[..end].trim_matches(|c: char| c == '-' || c.is_whitespace());
+        rest = &after[end + 2..];
+
+        let keyword = action.split_whitespace().next().unwrap_or("");
+        let rebound = blocks.iter().any(|b| *b);
+        for var in action_vars(action, rebound) {
+            if !vars.contains(&var) {
+                vars.push(var);
+            }
+        }
+        match keyword {
+            "range" | "with" => blocks.push(true),
+            "if" | "block" | "define" => blocks.push(false),
+            "end" => { blocks.pop(); }
+            _ => {}
+        }
+    }
+    vars
+}
+
+fn action_vars(action: &str, rebound: bool) -> Vec<String> {
+    let chars: Vec<char> = action.chars().collect();
+    let mut found = Vec::new();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' && (i == 0 || chars[i - 1] != '\\') {
+            in_string = !in_string;
+        } else if !in_string && c == '.' {
+            let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+            // `$.X` always refers to the root; a bare `.X` only does outside range/with
+            let root = match prev {
+                Some('$') => true,
+                Some(p) if p.is_alphanumeric() || p == '_' || p == ')' || p == '.' => false,
+                _ => !rebound,
+            };
+            let ident: String = chars[i + 1..]
+                .iter()
+                .take_while(|ch| ch.is_alphanumeric() || **ch == '_')
+                .collect();
+            if root && ident.starts_with(|ch: char| ch.is_ascii_uppercase()) {
+                found.push(ident.clone());
+            }
+            i += ident.len();
+        }
+        i += 1;
+    }
+    found
+}
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::State;
+
+/// Close matches for a model name the registry doesn't know; empty for any other failure
+async fn not_found_suggestions(http: State<'_, HttpClient>, url: &str, name: &str, error: &str, is_404: bool) -> Vec<String> {
+    let lower = error.to_lowercase();
+    let not_found = is_404
+        || lower.contains("file does not exist")
+        || lower.contains("not found")
+        || lower.contains("manifest unknown");
+    if !not_found {
+        return Vec::new();
+    }
+    let installed: Vec<String> = models_list(Some(url.to_string()), None, http)
+        .await
+        .map(|r| r.models.into_iter().map(|m| m.name).collect())
+        .unwrap_or_default();
+    match registry_client() {
+        Ok(client) => suggest_names(&client, name, &installed).await,
+        Err(_) => Vec::new(),
+    }
+}
+
+fn with_suggestions(error: String, suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        error
+    } else {
+        format!("{} (did you mean {}?)", error, suggestions.join(", "))
+    }
+}
+
+const PULL_CANCELLED: &str = "Cancelled by user";
+const PULL_DEADLINE_EXCEEDED: &str = "Deadline exceeded";
+const PULL_DISK_FULL: &str = "disk_full";
+const PULL_DUPLICATE: &str = "already pulling";
+
+lazy_static::lazy_static! {
+    // Pull id of the pull running (or queued) for each server and model
+    static ref ACTIVE_PULLS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// Holds a model's slot in `ACTIVE_PULLS`; dropping it frees the slot however the pull ended
+struct ActivePull(String);
+
+impl ActivePull {
+    /// The claim, or the id of the pull that already holds it
+    fn claim(server_url: &str, name: &str, pull_id: &str) -> Result<ActivePull, String> {
+        // `llama3` and `llama3:latest` are the same download
+        let name = if name.rsplit('/').next().is_some_and(|last| last.contains(':')) { name.to_string() } else { format!("{}:latest", name) };
+        let key = format!("{} {}", server_url, name);
+        let mut active = ACTIVE_PULLS.lock().unwrap();
+        if let Some(existing) = active.get(&key) {
+            return Err(existing.clone());
+        }
+        active.insert(key.clone(), pull_id.to_string());
+        Ok(ActivePull(key))
+    }
+}
+
+impl Drop for ActivePull {
+    fn drop(&mut self) {
+        ACTIVE_PULLS.lock().unwrap().remove(&self.0);
+    }
+}
+
+/// Free space below which a running pull is stopped before writes start failing
+const LOW_DISK_THRESHOLD_BYTES: u64 = 1024 * 1024 * 1024;
+const LOW_DISK_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Append-only progress log so a pull leaves a record even with no UI listening
+struct PullLog(std::fs::File);
+
+impl PullLog {
+    fn open(path: &str) -> Result<Self, String> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(PullLog)
+            .map_err(|e| format!("Failed to open pull log {}: {}", path, e))
+    }
+
+    // A full disk shouldn't abort the pull itself, so write errors are ignored
+    fn line(&mut self, text: &str) {
+        use std::io::Write;
+        let _ = writeln!(self.0, "{} {}", chrono::Utc::now().to_rfc3339(), text);
+    }
+}
+
+pub type CancellationMap = Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>;
+
+/// One pooled client shared by the commands, so repeated calls reuse connections.
+/// Timeouts differ per endpoint and are set on each request instead.
+#[derive(Clone)]
+pub struct HttpClient(Arc<std::sync::RwLock<(u64, reqwest::Client)>>);
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        HttpClient(Arc::new(std::sync::RwLock::new((settings_generation(), build_shared_client()))))
+    }
+}
+
+fn build_shared_client() -> reqwest::Client {
+    ollama_client_builder().build().unwrap_or_default()
+}
+
+impl HttpClient {
+    /// The current client, rebuilt first if the settings (proxies, profile headers) changed
+    /// since it was made. Cheap to clone; requests already sent keep the old one.
+    pub fn client(&self) -> reqwest::Client {
+        let generation = settings_generation();
+        {
+            let current = self.0.read().unwrap();
+            if current.0 == generation {
+                return current.1.clone();
+            }
+        }
+        let client = build_shared_client();
+        *self.0.write().unwrap() = (generation, client.clone());
+        client
+    }
+}
+
+/// Caps how many pulls download at once; the others wait for a permit
+pub struct PullLimiter {
+    semaphore: Arc<tokio::sync::Semaphore>,
+    permits: Mutex<usize>,
+}
+
+impl Default for PullLimiter {
+    fn default() -> Self {
+        let permits = crate::commands::settings::DEFAULT_MAX_CONCURRENT_PULLS;
+        PullLimiter { semaphore: Arc::new(tokio::sync::Semaphore::new(permits)), permits: Mutex::new(permits) }
+    }
+}
+
+impl PullLimiter {
+    /// Match the configured limit; permits held by running pulls are only given up as they finish
+    fn resize(&self, limit: usize) {
+        let mut permits = self.permits.lock().unwrap();
+        if limit > *permits {
+            self.semaphore.add_permits(limit - *permits);
+            *permits = limit;
+        } else if limit < *permits {
+            *permits -= self.semaphore.forget_permits(*permits - limit);
+        }
+    }
+}
+
+/// Latest known state of a pull, kept for clients that poll instead of listening to events
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PullProgress {
+    pub pull_id: String,
+    pub name: String,
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    pub done: bool,
+    pub error: Option<String>,
+    /// Epoch millis of the last update
+    pub updated_at: u64,
+}
+
+pub type PullStatusMap = Arc<Mutex<HashMap<String, PullProgress>>>;
+
+const FINISHED_PULL_RETENTION_MS: u64 = 10 * 60 * 1000;
+
+fn record_pull_status(app: &tauri::AppHandle, pull_id: &str, name: &str, update: impl FnOnce(&mut PullProgress)) {
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let state = app.state::<PullStatusMap>();
+    let mut map = state.lock().unwrap();
+    // Finished pulls linger long enough for a poller to see the outcome, then age out
+    map.retain(|_, p| !p.done || now.saturating_sub(p.updated_at) < FINISHED_PULL_RETENTION_MS);
+    let entry = map.entry(pull_id.to_string()).or_insert_with(|| PullProgress {
+        pull_id: pull_id.to_string(),
+        name: name.to_string(),
+        ..Default::default()
+    });
+    update(entry);
+    entry.updated_at = now;
+}
+
+fn record_pull_line(app: &tauri::AppHandle, pull_id: &str, name: &str, value: &serde_json::Value) {
+    record_pull_status(app, pull_id, name, |p| {
+        if let Some(status) = value.get("status").and_then(|s| s.as_str()) {
+            p.status = status.to_string();
+        }
+        p.digest = value.get("digest").and_then(|d| d.as_str()).map(str::to_string);
+        p.total = value.get("total").and_then(|t| t.as_u64());
+        p.completed = value.get("completed").and_then(|c| c.as_u64());
+    });
+}
+
+/// Poll the latest progress of a pull; finished pulls stay visible for a few minutes
+#[tauri::command]
+pub fn pull_status(pull_id: String, state: State<'_, PullStatusMap>) -> Option<PullProgress> {
+    state.lock().unwrap().get(&pull_id).cloned()
+}
+
+/// Wall-clock time spent in each stage of a pull, reported with `models:pull-complete`
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PullPhaseTimings {
+    pub manifest_ms: u64,
+    pub downloading_ms: u64,
+    pub verifying_ms: u64,
+    pub writing_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PullPhase {
+    Manifest,
+    Downloading,
+    Verifying,
+    Writing,
+}
+
+impl PullPhase {
+    // Ollama reports the stage only through the free-form `status` string
+    fn from_status(status: &str) -> Option<Self> {
+        if status.starts_with("pulling manifest") {
+            Some(PullPhase::Manifest)
+        } else if status.starts_with("pulling ") {
+            Some(PullPhase::Downloading)
+        } else if status.starts_with("verifying") {
+            Some(PullPhase::Verifying)
+        } else if status.starts_with("writing") || status.starts_with("removing") {
+            Some(PullPhase::Writing)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct PullPhaseTimer {
+    current: Option<(PullPhase, std::time::Instant)>,
+    timings: PullPhaseTimings,
+}
+
+impl PullPhaseTimer {
+    fn is_downloading(&self) -> bool {
+        matches!(self.current, Some((PullPhase::Downloading, _)))
+    }
+
+    fn observe(&mut self, status: &str) {
+        let phase = PullPhase::from_status(status);
+        if self.current.map(|(p, _)| p) == phase {
+            return;
         }
-        Ok(r) => return Ok(SimpleResponse { success: false, error: Some(format!("HTTP error: {}", r.status())) }),
-        Err(e) => return Ok(SimpleResponse { success: false, error: Some(format!("Request error: {}", e)) }),
-    };
+        self.close_current();
+        self.current = phase.map(|p| (p, std::time::Instant::now()));
+    }
 
-    if resp.status().is_success() {
-        Ok(SimpleResponse { success: true, error: None })
-    } else {
-        Ok(SimpleResponse { success: false, error: Some(format!("HTTP error: {}", resp.status())) })
+    fn close_current(&mut self) {
+        if let Some((phase, started)) = self.current.take() {
+            let ms = started.elapsed().as_millis() as u64;
+            match phase {
+                PullPhase::Manifest => self.timings.manifest_ms += ms,
+                PullPhase::Downloading => self.timings.downloading_ms += ms,
+                PullPhase::Verifying => self.timings.verifying_ms += ms,
+                PullPhase::Writing => self.timings.writing_ms += ms,
+            }
+        }
+    }
+
+    fn finish(mut self) -> PullPhaseTimings {
+        self.close_current();
+        self.timings
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ShowResponse {
-    pub modelfile: Option<String>,
-    pub parameters: Option<serde_json::Value>,
-    pub template: Option<String>,
-    pub license: Option<String>,
-    #[serde(flatten)]
-    pub extra: std::collections::HashMap<String, serde_json::Value>,
+// Speed is averaged over this window so bursty chunks don't make it jump around
+const PULL_RATE_WINDOW: std::time::Duration = std::time::Duration::from_secs(5);
+// With no new bytes for this long the pull counts as stalled
+const PULL_STALL_AFTER: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Progress across every layer of a pull, since Ollama only reports the current layer
+#[derive(Default)]
+struct PullTotals {
+    /// (completed, total) per layer digest
+    layers: HashMap<String, (u64, u64)>,
+    percent: f64,
+    /// (when, completed bytes) each time the byte count moved, covering `PULL_RATE_WINDOW`
+    samples: std::collections::VecDeque<(std::time::Instant, u64)>,
+    /// Re-sent when the stream goes quiet so the UI sees the stall
+    last_line: Option<serde_json::Value>,
 }
 
-#[tauri::command]
-pub async fn model_show(name: String, server_url: Option<String>) -> Result<ShowResponse, String> {
-    let url = server_url.unwrap_or_else(get_ollama_url);
-    let endpoint = format!("{}/api/show", url);
+impl PullTotals {
+    fn observe(&mut self, line: &serde_json::Value) {
+        self.last_line = Some(line.clone());
+        if line.get("status").and_then(|s| s.as_str()) == Some("success") {
+            self.percent = 100.0;
+            return;
+        }
+        let Some(digest) = line.get("digest").and_then(|d| d.as_str()) else { return };
+        // A layer's first line can carry `total` with no `completed` yet
+        let layer = self.layers.entry(digest.to_string()).or_default();
+        if let Some(completed) = line.get("completed").and_then(|c| c.as_u64()) {
+            layer.0 = layer.0.max(completed);
+        }
+        if let Some(total) = line.get("total").and_then(|t| t.as_u64()) {
+            layer.1 = total;
+        }
+        let (completed, total) = self.bytes();
+        // A new layer raises the total; hold the figure rather than let it slide back
+        if total > 0 {
+            self.percent = self.percent.max(completed as f64 * 100.0 / total as f64);
+        }
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| e.to_string())?;
+        let now = std::time::Instant::now();
+        if self.samples.back().map_or(true, |(_, bytes)| *bytes != completed) {
+            self.samples.push_back((now, completed));
+        }
+        // Keep one sample at or before the window start so the average spans all of it
+        while self.samples.len() > 2 && self.samples.get(1).is_some_and(|(at, _)| now.duration_since(*at) >= PULL_RATE_WINDOW) {
+            self.samples.pop_front();
+        }
+    }
 
-    // Use POST body per Ollama API examples
-    let resp = client
-        .post(&endpoint)
-        .json(&serde_json::json!({ "name": name }))
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    /// Average over the last few seconds, measured up to now so a stall pulls it down
+    fn bytes_per_sec(&self) -> f64 {
+        let (Some((first_at, first)), Some((last_at, last))) = (self.samples.front(), self.samples.back()) else { return 0.0 };
+        if last_at.elapsed() >= PULL_STALL_AFTER {
+            return 0.0;
+        }
+        let secs = first_at.elapsed().as_secs_f64();
+        if secs <= 0.0 || last <= first {
+            return 0.0;
+        }
+        (last - first) as f64 / secs
+    }
 
-    if !resp.status().is_success() {
-        return Err(format!("HTTP error: {}", resp.status()));
+    /// None while stalled or before there is a rate to go on
+    fn eta_seconds(&self) -> Option<u64> {
+        let rate = self.bytes_per_sec();
+        let (completed, total) = self.bytes();
+        (rate > 0.0).then(|| (total.saturating_sub(completed) as f64 / rate).ceil() as u64)
     }
 
-    resp.json::<ShowResponse>().await.map_err(|e| e.to_string())
-}
+    /// Completed and total bytes over the layers seen so far
+    fn bytes(&self) -> (u64, u64) {
+        self.layers.values().fold((0, 0), |(c, t), (lc, lt)| (c + lc.min(lt), t + lt))
+    }
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::State;
+    fn percent(&self) -> f64 {
+        (self.percent * 10.0).round() / 10.0
+    }
+}
 
-pub type CancellationMap = Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>;
+fn emit_pull_progress(app: &tauri::AppHandle, pull_id: &str, line: &serde_json::Value, totals: &PullTotals) {
+    let (overall_bytes, overall_total) = totals.bytes();
+    let _ = app.emit(events::MODELS_PULL_PROGRESS, &serde_json::json!({
+        "pull_id": pull_id,
+        "progress": line,
+        "overall_percent": totals.percent(),
+        "overall_bytes": overall_bytes,
+        "overall_total": overall_total,
+        "bytes_per_sec": totals.bytes_per_sec().round() as u64,
+        "eta_seconds": totals.eta_seconds()
+    }));
+}
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // Tauri commands take their state as parameters
 pub async fn model_pull(
     app: tauri::AppHandle,
     name: String,
     pull_id: Option<String>,
     server_url: Option<String>,
+    log_to_file: Option<String>,
+    deadline_secs: Option<u64>,
     state: State<'_, CancellationMap>,
-) -> Result<SimpleResponse, String> {
+    http: State<'_, HttpClient>,
+) -> Result<SimpleResponse, OllieError> {
+    ensure_writable("model_pull")?;
+    let deadline = deadline_secs.map(|secs| tokio::time::Instant::now() + std::time::Duration::from_secs(secs));
+    let mut log = log_to_file.as_deref().map(PullLog::open).transpose()?;
     let url = server_url.unwrap_or_else(get_ollama_url);
     let endpoint = format!("{}/api/pull", url);
 
     let pull_id = pull_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    // A second pull of the same model would only race the first; point the caller at it instead
+    let _active = match ActivePull::claim(&url, &name, &pull_id) {
+        Ok(active) => active,
+        Err(existing_pull_id) => {
+            let _ = app.emit(events::MODELS_PULL_DUPLICATE, &serde_json::json!({
+                "pull_id": pull_id,
+                "name": name,
+                "existing_pull_id": existing_pull_id
+            }));
+            return Ok(SimpleResponse { success: false, error: Some(PULL_DUPLICATE.to_string()) });
+        }
+    };
     
-    // Register cancellation token
-    let cancel_flag = Arc::new(AtomicBool::new(false));
-    {
+    // Register cancellation token; a queued pull already has one, possibly already set
+    let cancel_flag = {
         let mut map = state.lock().unwrap();
-        map.insert(pull_id.clone(), cancel_flag.clone());
+        map.entry(pull_id.clone()).or_insert_with(|| Arc::new(AtomicBool::new(false))).clone()
+    };
+
+    // Wait for a download slot, watching for a cancel meanwhile
+    let limiter = app.state::<PullLimiter>();
+    limiter.resize(max_concurrent_pulls().await);
+    let mut permit = limiter.semaphore.clone().try_acquire_owned().ok();
+    if permit.is_none() {
+        record_pull_status(&app, &pull_id, &name, |p| p.status = "queued".to_string());
+        let _ = app.emit(events::MODELS_PULL_QUEUED, &serde_json::json!({ "pull_id": pull_id, "name": name }));
+        while !cancel_flag.load(Ordering::Relaxed) {
+            let acquire = limiter.semaphore.clone().acquire_owned();
+            if let Ok(acquired) = tokio::time::timeout(std::time::Duration::from_millis(250), acquire).await {
+                permit = acquired.ok();
+                break;
+            }
+        }
     }
+    let _permit = permit;
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(60 * 60)) // up to 1 hour
-        .build()
-        .map_err(|e| e.to_string())?;
+    // Cancelled while still queued: drop it without ever contacting the server
+    if cancel_flag.load(Ordering::Relaxed) {
+        state.lock().unwrap().remove(&pull_id);
+        if take_pause(&pull_id, &name, &url, &log_to_file) {
+            record_pull_status(&app, &pull_id, &name, |p| p.status = "paused".to_string());
+            let _ = app.emit(events::MODELS_PULL_PAUSED, &serde_json::json!({ "pull_id": pull_id, "name": name }));
+            return Ok(SimpleResponse { success: false, error: Some(PULL_PAUSED.to_string()) });
+        }
+        record_pull_status(&app, &pull_id, &name, |p| {
+            p.status = "cancelled".to_string();
+            p.done = true;
+        });
+        let _ = app.emit(events::MODELS_PULL_CANCELLED, &serde_json::json!({ "pull_id": pull_id, "reason": "user", "queued": true }));
+        return Ok(SimpleResponse { success: false, error: Some(PULL_CANCELLED.to_string()) });
+    }
 
     // notify frontend pull started
-    let _ = app.emit("models:pull-start", &serde_json::json!({ "pull_id": pull_id, "name": name }));
+    let _ = app.emit(events::MODELS_PULL_START, &serde_json::json!({ "pull_id": pull_id, "name": name }));
+    if let Some(log) = log.as_mut() {
+        log.line(&format!("start {} ({})", name, pull_id));
+    }
+    record_pull_status(&app, &pull_id, &name, |p| p.status = "starting".to_string());
 
-    let response = client
-        .post(&endpoint)
-        .json(&serde_json::json!({ "name": name }))
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let mut request = http.client().post(&endpoint).json(&serde_json::json!({ "name": name }));
+    if let Some(timeout) = timeouts().await.pull() {
+        request = request.timeout(timeout);
+    }
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            state.lock().unwrap().remove(&pull_id);
+            record_pull_status(&app, &pull_id, &name, |p| {
+                p.status = "error".to_string();
+                p.error = Some(e.to_string());
+                p.done = true;
+            });
+            return Err(e.into());
+        }
+    };
 
     if !response.status().is_success() {
-        let _ = app.emit("models:pull-error", &serde_json::json!({ "pull_id": pull_id, "error": format!("HTTP error: {}", response.status()) }));
-        return Ok(SimpleResponse { success: false, error: Some(format!("HTTP error: {}", response.status())) });
+        let status = response.status();
+        // Ollama puts the reason in a JSON `error` field
+        let body = response.text().await.unwrap_or_default();
+        let reason = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+        let error = match reason {
+            Some(reason) => format!("HTTP error: {}: {}", status, reason),
+            None => format!("HTTP error: {}", status),
+        };
+        if let Some(log) = log.as_mut() {
+            log.line(&error);
+        }
+        state.lock().unwrap().remove(&pull_id);
+        let suggestions = not_found_suggestions(http.clone(), &url, &name, &error, status == reqwest::StatusCode::NOT_FOUND).await;
+        let error = with_suggestions(error, &suggestions);
+        record_pull_status(&app, &pull_id, &name, |p| {
+            p.status = "error".to_string();
+            p.error = Some(error.clone());
+            p.done = true;
+        });
+        let _ = app.emit(events::MODELS_PULL_ERROR, &serde_json::json!({ "pull_id": pull_id, "error": error, "suggestions": suggestions }));
+        return Ok(SimpleResponse { success: false, error: Some(error) });
     }
 
     // Stream NDJSON progress
     let mut stream = response.bytes_stream();
     let mut buffer = String::new();
+    let mut phase_timer = PullPhaseTimer::default();
+    let mut totals = PullTotals::default();
+    // Only a local server writes to a disk we can see, unless settings point at one
+    let settings = crate::commands::settings::settings_get().await.ok();
+    let models_dir = match settings.as_ref().filter(|s| s.pull_disk_check != Some(false)) {
+        None => None,
+        Some(s) => match s.pull_disk_check_path.as_deref().filter(|p| !p.trim().is_empty()) {
+            Some(path) => Some(std::path::PathBuf::from(path)),
+            None => matches!(crate::commands::sys::server_locality(Some(url.clone())), Ok(crate::commands::sys::Locality::Local))
+                .then(ollama_models_dir),
+        },
+    };
+    let mut last_disk_check = std::time::Instant::now();
+    let mut preflight_done = models_dir.is_none();
 
     let result = loop {
      // Check cancellation
+     if deadline.is_some_and(|at| tokio::time::Instant::now() >= at) {
+         cancel_flag.store(true, Ordering::Relaxed);
+         break Err(PULL_DEADLINE_EXCEEDED.to_string());
+     }
      if cancel_flag.load(Ordering::Relaxed) {
-         break Err("Cancelled by user".to_string());
+         break Err(PULL_CANCELLED.to_string());
+     }
+     if let Some(dir) = models_dir.as_deref().filter(|_| phase_timer.is_downloading() && last_disk_check.elapsed() >= LOW_DISK_CHECK_INTERVAL) {
+         last_disk_check = std::time::Instant::now();
+         if let Some(free_bytes) = available_space(dir).filter(|free| *free < LOW_DISK_THRESHOLD_BYTES) {
+             let _ = app.emit(events::MODELS_PULL_LOWDISK, &serde_json::json!({
+                 "pull_id": pull_id,
+                 "name": name,
+                 "free_bytes": free_bytes,
+                 "threshold_bytes": LOW_DISK_THRESHOLD_BYTES
+             }));
+             break Err(PULL_DISK_FULL.to_string());
+         }
      }
 
-     match stream.next().await {
+     // A stalled stream must not outlive the deadline, so bound the wait for the next chunk
+     let wake = tokio::time::Instant::now() + PULL_STALL_AFTER;
+     let next = match tokio::time::timeout_at(deadline.map_or(wake, |at| at.min(wake)), stream.next()).await {
+         Ok(next) => next,
+         Err(_) => {
+             // Repeat the last line so the speed and ETA show the stall instead of going stale
+             if let Some(line) = totals.last_line.clone() {
+                 emit_pull_progress(&app, &pull_id, &line, &totals);
+             }
+             continue;
+         }
+     };
+     match next {
         Some(chunk) => {
             match chunk {
                 Ok(bytes) => {
-                    let chunk_str = String::from_utf8_lossy(&bytes);
-                    buffer.push_str(&chunk_str);
-                    loop {
-                        if let Some(pos) = buffer.find('\n') {
-                            let line = buffer[..pos].trim().to_string();
-                            buffer = buffer[pos + 1..].to_string();
-                            if line.is_empty() { continue; }
-                            // Forward raw JSON line as progress to UI
-                            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) {
-                                let _ = app.emit("models:pull-progress", &serde_json::json!({
-                                    "pull_id": pull_id,
-                                    "progress": value
-                                }));
-                            } else {
-                                let _ = app.emit("models:pull-progress", &serde_json::json!({
-                                    "pull_id": pull_id,
-                                    "progress": { "status": "parsing_error", "raw": line }
-                                }));
+                    buffer.push_str(&String::from_utf8_lossy(&bytes));
+                    let mut server_error = None;
+                    for line in take_ndjson_lines(&mut buffer) {
+                        if let Some(log) = log.as_mut() {
+                            log.line(&line);
+                        }
+                        // Forward raw JSON line as progress to UI
+                        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) {
+                            // Failures such as an unknown model arrive as a line, not a status code
+                            if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+                                server_error = Some(error.to_string());
+                                break;
+                            }
+                            if let Some(status) = value.get("status").and_then(|s| s.as_str()) {
+                                phase_timer.observe(status);
+                            }
+                            // The first sized layer is the weights, so it alone says whether the pull can fit
+                            if let (false, Some(total), Some(dir)) = (preflight_done, value.get("total").and_then(|t| t.as_u64()), models_dir.as_deref()) {
+                                preflight_done = true;
+                                let needed = total.saturating_sub(value.get("completed").and_then(|c| c.as_u64()).unwrap_or(0));
+                                if let Some(free) = available_space(dir).filter(|free| *free < needed) {
+                                    server_error = Some(format!("insufficient disk space: {} bytes needed, {} free", needed, free));
+                                    break;
+                                }
                             }
+                            record_pull_line(&app, &pull_id, &name, &value);
+                            totals.observe(&value);
+                            emit_pull_progress(&app, &pull_id, &value, &totals);
                         } else {
-                            break;
+                            let _ = app.emit(events::MODELS_PULL_PROGRESS, &serde_json::json!({
+                                "pull_id": pull_id,
+                                "progress": { "status": "parsing_error", "raw": line }
+                            }));
                         }
                     }
+                    if let Some(error) = server_error {
+                        break Err(error);
+                    }
                 }
                 Err(e) => {
                     break Err(e.to_string());
@@ -228,36 +1387,220 @@ pub async fn model_pull(
         let mut map = state.lock().unwrap();
         map.remove(&pull_id);
     }
+    // A pause that arrived as the pull finished or failed has nothing left to resume
+    if result.as_ref().err().map(String::as_str) != Some(PULL_CANCELLED) {
+        PAUSED_PULLS.lock().unwrap().remove(&pull_id);
+    }
 
     if let Err(e) = result {
-        if e == "Cancelled by user" {
-            let _ = app.emit("models:pull-cancelled", &serde_json::json!({ "pull_id": pull_id }));
-        } else {
-            let _ = app.emit("models:pull-error", &serde_json::json!({ "pull_id": pull_id, "error": e.clone() }));
+        if let Some(log) = log.as_mut() {
+            log.line(&format!("failed: {}", e));
+        }
+        if e == PULL_CANCELLED && take_pause(&pull_id, &name, &url, &log_to_file) {
+            record_pull_status(&app, &pull_id, &name, |p| p.status = "paused".to_string());
+            let _ = app.emit(events::MODELS_PULL_PAUSED, &serde_json::json!({ "pull_id": pull_id, "name": name }));
+            return Ok(SimpleResponse { success: false, error: Some(PULL_PAUSED.to_string()) });
         }
+        if e == PULL_CANCELLED || e == PULL_DEADLINE_EXCEEDED || e == PULL_DISK_FULL {
+            let reason = match e.as_str() {
+                PULL_DEADLINE_EXCEEDED => "deadline_exceeded",
+                PULL_DISK_FULL => PULL_DISK_FULL,
+                _ => "user",
+            };
+            record_pull_status(&app, &pull_id, &name, |p| {
+                p.status = "cancelled".to_string();
+                p.error = (reason != "user").then(|| e.clone());
+                p.done = true;
+            });
+            let _ = app.emit(events::MODELS_PULL_CANCELLED, &serde_json::json!({ "pull_id": pull_id, "reason": reason }));
+            return Ok(SimpleResponse { success: false, error: Some(e) });
+        }
+        let suggestions = not_found_suggestions(http.clone(), &url, &name, &e, false).await;
+        let e = with_suggestions(e, &suggestions);
+        record_pull_status(&app, &pull_id, &name, |p| {
+            p.status = "error".to_string();
+            p.error = Some(e.clone());
+            p.done = true;
+        });
+        let _ = app.emit(events::MODELS_PULL_ERROR, &serde_json::json!({ "pull_id": pull_id, "error": e, "suggestions": suggestions }));
         return Ok(SimpleResponse { success: false, error: Some(e) });
     }
 
     // Any trailing buffered line
     if !buffer.trim().is_empty() {
         let line = buffer.trim();
+        if let Some(log) = log.as_mut() {
+            log.line(line);
+        }
         if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
-            let _ = app.emit("models:pull-progress", &serde_json::json!({
-                "pull_id": pull_id,
-                "progress": value
-            }));
+            if let Some(status) = value.get("status").and_then(|s| s.as_str()) {
+                phase_timer.observe(status);
+            }
+            record_pull_line(&app, &pull_id, &name, &value);
+            totals.observe(&value);
+            emit_pull_progress(&app, &pull_id, &value, &totals);
         }
     }
 
-    let _ = app.emit("models:pull-complete", &serde_json::json!({ "pull_id": pull_id }));
+    let phases = phase_timer.finish();
+    record_pull_status(&app, &pull_id, &name, |p| {
+        p.status = "success".to_string();
+        p.done = true;
+    });
+    if let Some(log) = log.as_mut() {
+        log.line(&format!("complete {}", serde_json::to_string(&phases).unwrap_or_default()));
+    }
+    let _ = app.emit(events::MODELS_PULL_COMPLETE, &serde_json::json!({
+        "pull_id": pull_id,
+        "phases": phases
+    }));
     Ok(SimpleResponse { success: true, error: None })
 }
 
+/// Split the complete lines off an NDJSON buffer, leaving any partial line in place
+pub(crate) fn take_ndjson_lines(buffer: &mut String) -> Vec<String> {
+    let Some(end) = buffer.rfind('\n') else { return Vec::new() };
+    let lines = buffer[..end]
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect();
+    buffer.drain(..=end);
+    lines
+}
+
+/// Build `name` from a Modelfile via `/api/create`, streaming `models:create-progress`.
+/// Cancel with `model_create_cancel(create_id)`.
+#[tauri::command]
+pub async fn model_create(
+    app: tauri::AppHandle,
+    name: String,
+    modelfile: String,
+    create_id: Option<String>,
+    server_url: Option<String>,
+    state: State<'_, CancellationMap>,
+    http: State<'_, HttpClient>,
+) -> Result<SimpleResponse, OllieError> {
+    ensure_writable("model_create")?;
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let create_id = create_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    // Register cancellation token
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut map = state.lock().unwrap();
+        map.insert(create_id.clone(), cancel_flag.clone());
+    }
+
+    let result = stream_create(&app, &http.client(), &url, &name, &modelfile, &create_id, &cancel_flag).await;
+
+    // Cleanup cancellation token
+    {
+        let mut map = state.lock().unwrap();
+        map.remove(&create_id);
+    }
+
+    match result {
+        Ok(()) => {
+            let _ = app.emit(events::MODELS_CREATE_COMPLETE, &serde_json::json!({ "create_id": create_id, "name": name }));
+            Ok(SimpleResponse { success: true, error: None })
+        }
+        Err(e) if e == PULL_CANCELLED => {
+            let _ = app.emit(events::MODELS_CREATE_CANCELLED, &serde_json::json!({ "create_id": create_id, "name": name }));
+            Ok(SimpleResponse { success: false, error: Some(e) })
+        }
+        Err(e) => {
+            let _ = app.emit(events::MODELS_CREATE_ERROR, &serde_json::json!({ "create_id": create_id, "error": e }));
+            Ok(SimpleResponse { success: false, error: Some(e) })
+        }
+    }
+}
+
+async fn stream_create(
+    app: &tauri::AppHandle,
+    http: &reqwest::Client,
+    url: &str,
+    name: &str,
+    modelfile: &str,
+    create_id: &str,
+    cancel_flag: &AtomicBool,
+) -> Result<(), String> {
+    let response = http
+        .post(format!("{}/api/create", url))
+        // Quantizing or copying large blobs can take a while
+        .timeout(std::time::Duration::from_secs(60 * 60))
+        .json(&serde_json::json!({ "name": name, "model": name, "modelfile": modelfile, "stream": true }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let reason = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+        return Err(match reason {
+            Some(reason) => format!("HTTP error: {}: {}", status, reason),
+            None => format!("HTTP error: {}", status),
+        });
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+    let emit_line = |line: &str| -> Result<(), String> {
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(value) => {
+                if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+                    return Err(error.to_string());
+                }
+                let _ = app.emit(events::MODELS_CREATE_PROGRESS, &serde_json::json!({ "create_id": create_id, "progress": value }));
+            }
+            Err(_) => {
+                let _ = app.emit(events::MODELS_CREATE_PROGRESS, &serde_json::json!({
+                    "create_id": create_id,
+                    "progress": { "status": "parsing_error", "raw": line }
+                }));
+            }
+        }
+        Ok(())
+    };
+    while let Some(chunk) = stream.next().await {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return Err(PULL_CANCELLED.to_string());
+        }
+        buffer.push_str(&String::from_utf8_lossy(&chunk.map_err(|e| e.to_string())?));
+        for line in take_ndjson_lines(&mut buffer) {
+            emit_line(&line)?;
+        }
+    }
+    // Any trailing buffered line
+    let line = buffer.trim();
+    if !line.is_empty() {
+        emit_line(line)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn model_create_cancel(
+    create_id: String,
+    state: State<'_, CancellationMap>,
+) -> Result<SimpleResponse, OllieError> {
+    let map = state.lock().unwrap();
+    if let Some(flag) = map.get(&create_id) {
+        flag.store(true, Ordering::Relaxed);
+        Ok(SimpleResponse { success: true, error: None })
+    } else {
+        Ok(SimpleResponse { success: false, error: Some("Create ID not found".to_string()) })
+    }
+}
+
 #[tauri::command]
 pub async fn model_pull_cancel(
     pull_id: String,
     state: State<'_, CancellationMap>,
-) -> Result<SimpleResponse, String> {
+) -> Result<SimpleResponse, OllieError> {
     let map = state.lock().unwrap();
     if let Some(flag) = map.get(&pull_id) {
         flag.store(true, Ordering::Relaxed);
@@ -265,4 +1608,349 @@ pub async fn model_pull_cancel(
     } else {
         Ok(SimpleResponse { success: false, error: Some("Pull ID not found".to_string()) })
     }
-}
\ No newline at end of file
+}
+
+/// Signal every running or queued pull to stop; each still emits its own
+/// `models:pull-cancelled`. Chats and other streams sharing the map are left alone.
+#[tauri::command]
+pub fn model_pull_cancel_all(state: State<'_, CancellationMap>, pulls: State<'_, PullStatusMap>) -> usize {
+    let pull_ids: Vec<String> = pulls.lock().unwrap().values().filter(|p| !p.done).map(|p| p.pull_id.clone()).collect();
+    let map = state.lock().unwrap();
+    pull_ids
+        .iter()
+        .filter_map(|id| map.get(id))
+        .filter(|flag| !flag.swap(true, Ordering::Relaxed))
+        .count()
+}
+
+const PULL_PAUSED: &str = "Paused";
+
+/// What `model_pull_resume` needs to re-issue a paused pull
+#[derive(Debug, Clone)]
+struct PausedPull {
+    name: String,
+    server_url: String,
+    log_to_file: Option<String>,
+}
+
+lazy_static::lazy_static! {
+    // None while the pause is requested but the pull loop hasn't stopped yet
+    static ref PAUSED_PULLS: Mutex<HashMap<String, Option<PausedPull>>> = Mutex::new(HashMap::new());
+}
+
+/// Called once a pull stops on its cancel flag: if that was a pause, remember how to resume it
+fn take_pause(pull_id: &str, name: &str, server_url: &str, log_to_file: &Option<String>) -> bool {
+    match PAUSED_PULLS.lock().unwrap().get_mut(pull_id) {
+        Some(slot) => {
+            *slot = Some(PausedPull {
+                name: name.to_string(),
+                server_url: server_url.to_string(),
+                log_to_file: log_to_file.clone(),
+            });
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stop a pull without giving it up; blobs already on disk are kept and `model_pull_resume`
+/// picks up from them
+#[tauri::command]
+pub async fn model_pull_pause(
+    pull_id: String,
+    state: State<'_, CancellationMap>,
+) -> Result<SimpleResponse, OllieError> {
+    let map = state.lock().unwrap();
+    let Some(flag) = map.get(&pull_id) else {
+        return Ok(SimpleResponse { success: false, error: Some("Pull ID not found".to_string()) });
+    };
+    PAUSED_PULLS.lock().unwrap().insert(pull_id, None);
+    flag.store(true, Ordering::Relaxed);
+    Ok(SimpleResponse { success: true, error: None })
+}
+
+/// Re-issue a paused pull under the same id; Ollama skips the blobs it already has
+#[tauri::command]
+pub async fn model_pull_resume(
+    app: tauri::AppHandle,
+    pull_id: String,
+    state: State<'_, CancellationMap>,
+    http: State<'_, HttpClient>,
+) -> Result<SimpleResponse, OllieError> {
+    let paused = {
+        let mut paused = PAUSED_PULLS.lock().unwrap();
+        match paused.get(&pull_id) {
+            Some(Some(_)) => paused.remove(&pull_id).flatten(),
+            Some(None) => return Ok(SimpleResponse { success: false, error: Some("Pull is still pausing".to_string()) }),
+            None => None,
+        }
+    };
+    let Some(paused) = paused else {
+        return Ok(SimpleResponse { success: false, error: Some("No paused pull with that ID".to_string()) });
+    };
+    let _ = app.emit(events::MODELS_PULL_RESUMED, &serde_json::json!({ "pull_id": pull_id, "name": paused.name }));
+    model_pull(app, paused.name, Some(pull_id), Some(paused.server_url), paused.log_to_file, None, state, http).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ManifestPullResult {
+    pub batch_id: String,
+    pub pulled: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<ManifestFailure>,
+}
+
+/// Model names from a JSON array, a `{"models": [...]}` object, or plain text
+/// with one name per line (blank lines and `#` comments ignored)
+fn parse_model_manifest(content: &str) -> Result<Vec<String>, String> {
+    let names = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(serde_json::Value::Object(mut obj)) => match obj.remove("models") {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => return Err("Manifest object must have a \"models\" array".to_string()),
+        },
+        Ok(_) => return Err("Manifest must be a list of model names".to_string()),
+        Err(_) => {
+            return Ok(content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(str::to_string)
+                .collect())
+        }
+    };
+    names
+        .into_iter()
+        .map(|v| v.as_str().map(|s| s.trim().to_string()).ok_or_else(|| format!("Not a model name: {}", v)))
+        .collect()
+}
+
+/// Provision every model listed in `path`, skipping installed models whose digest matches
+/// the registry. Pulls run `concurrency` at a time (default 1) and each emits the usual
+/// `models:pull-*` events; the aggregate is reported as a `pull_manifest` batch.
+#[tauri::command]
+pub async fn models_pull_manifest(
+    app: tauri::AppHandle,
+    path: String,
+    server_url: Option<String>,
+    concurrency: Option<usize>,
+    batch_id: Option<String>,
+    state: State<'_, CancellationMap>,
+    http: State<'_, HttpClient>,
+) -> Result<ManifestPullResult, OllieError> {
+    ensure_writable("models_pull_manifest")?;
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut names = parse_model_manifest(&content)?;
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|n| seen.insert(n.clone()));
+
+    let installed: HashMap<String, String> = models_list(server_url.clone(), None, http.clone())
+        .await?
+        .models
+        .into_iter()
+        .map(|m| (m.name, m.digest))
+        .collect();
+    let client = registry_client()?;
+
+    let mut result = ManifestPullResult::default();
+    let mut to_pull = Vec::new();
+    for name in names {
+        let full_name = if name.contains(':') { name.clone() } else { format!("{}:latest", name) };
+        let up_to_date = match (installed.get(&full_name), ModelRef::parse(&name)) {
+            (Some(local), Ok(model_ref)) => remote_digest(&client, &model_ref).await.is_ok_and(|d| &d == local),
+            _ => false,
+        };
+        if up_to_date {
+            result.skipped.push(name);
+        } else {
+            to_pull.push(name);
+        }
+    }
+
+    let mut batch = BatchProgress::start(&app, batch_id, "pull_manifest", to_pull.len() + result.skipped.len());
+    result.batch_id = batch.id().to_string();
+    for name in &result.skipped {
+        batch.item_done(name, true);
+    }
+
+    // Register every pull up front so `model_pull_cancel` can dequeue one before it starts
+    let queued: Vec<(String, String)> = to_pull
+        .into_iter()
+        .map(|name| {
+            let pull_id = uuid::Uuid::new_v4().to_string();
+            state.lock().unwrap().insert(pull_id.clone(), Arc::new(AtomicBool::new(false)));
+            record_pull_status(&app, &pull_id, &name, |p| p.status = "queued".to_string());
+            let _ = app.emit(events::MODELS_PULL_QUEUED, &serde_json::json!({ "pull_id": pull_id, "name": name }));
+            (pull_id, name)
+        })
+        .collect();
+
+    let pulls = futures_util::stream::iter(queued.into_iter().map(|(pull_id, name)| {
+        let (app, server_url, state, http) = (app.clone(), server_url.clone(), state.clone(), http.clone());
+        async move {
+            let outcome = model_pull(app, name.clone(), Some(pull_id), server_url, None, None, state, http).await;
+            (name, outcome)
+        }
+    }))
+    .buffer_unordered(concurrency.unwrap_or(1).max(1));
+    futures_util::pin_mut!(pulls);
+
+    while let Some((name, outcome)) = pulls.next().await {
+        let error = match outcome {
+            Ok(SimpleResponse { success: true, .. }) => None,
+            Ok(SimpleResponse { error, .. }) => Some(error.unwrap_or_else(|| "Pull failed".to_string())),
+            Err(error) => Some(error.to_string()),
+        };
+        batch.item_done(&name, error.is_none());
+        match error {
+            None => result.pulled.push(name),
+            Some(error) => result.failed.push(ManifestFailure { name, error }),
+        }
+    }
+    batch.finish();
+
+    Ok(result)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SmokeTestResult {
+    pub ok: bool,
+    pub output: Option<String>,
+    /// Time Ollama reported spending on loading the model
+    pub load_ms: Option<u64>,
+    pub total_ms: u64,
+    pub error: Option<String>,
+}
+
+const SMOKE_TEST_TIMEOUT_SECS: u64 = 90;
+
+/// Run one tiny generation to confirm a model loads and produces text
+#[tauri::command]
+pub async fn model_smoke_test(name: String, server_url: Option<String>, http: State<'_, HttpClient>) -> Result<SmokeTestResult, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+
+    let started = std::time::Instant::now();
+    let response = http
+        .client()
+        .post(format!("{}/api/generate", url))
+        // Generous enough for a cold load of a large model, short enough to flag a hang
+        .timeout(std::time::Duration::from_secs(SMOKE_TEST_TIMEOUT_SECS))
+        .json(&serde_json::json!({
+            "model": name,
+            "prompt": "Say OK",
+            "stream": false,
+            "options": { "num_predict": 8 }
+        }))
+        .send()
+        .await;
+    let failed = |error: String| SmokeTestResult {
+        ok: false,
+        output: None,
+        load_ms: None,
+        total_ms: started.elapsed().as_millis() as u64,
+        error: Some(error),
+    };
+
+    let response = match response {
+        Ok(r) => r,
+        Err(e) if e.is_timeout() => return Ok(failed(format!("No response within {}s", SMOKE_TEST_TIMEOUT_SECS))),
+        Err(e) => return Ok(failed(e.to_string())),
+    };
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Ok(failed(format!("HTTP error: {} {}", status, body.trim())));
+    }
+    let body: serde_json::Value = match response.json().await {
+        Ok(v) => v,
+        Err(e) => return Ok(failed(format!("Failed to parse response: {}", e))),
+    };
+
+    let output = body.get("response").and_then(|r| r.as_str()).unwrap_or_default().trim().to_string();
+    let ok = !output.is_empty();
+    Ok(SmokeTestResult {
+        ok,
+        error: (!ok).then(|| "Model returned no output".to_string()),
+        output: Some(output),
+        load_ms: body.get("load_duration").and_then(|d| d.as_u64()).map(|ns| ns / 1_000_000),
+        total_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromptEvalSpeed {
+    pub requested_tokens: usize,
+    /// Tokens the server actually processed; the prompt is only sized approximately
+    pub prompt_eval_count: u64,
+    pub prompt_eval_ms: u64,
+    pub tokens_per_sec: f64,
+}
+
+const MAX_PROMPT_EVAL_TOKENS: usize = 128 * 1024;
+
+// Common short words that each encode to a single token in most vocabularies
+const FILLER_WORDS: [&str; 16] = [
+    "the", "of", "and", "to", "in", "is", "it", "that", "for", "on", "was", "with", "as", "at", "by", "from",
+];
+
+/// Time how fast `name` ingests a prompt of roughly `prompt_tokens` tokens, generating a
+/// single token so the measurement is almost all prompt processing
+#[tauri::command]
+pub async fn model_prompt_eval_speed(
+    name: String,
+    prompt_tokens: usize,
+    server_url: Option<String>,
+    http: State<'_, HttpClient>,
+) -> Result<PromptEvalSpeed, OllieError> {
+    if prompt_tokens == 0 || prompt_tokens > MAX_PROMPT_EVAL_TOKENS {
+        return Err(format!("prompt_tokens must be between 1 and {}", MAX_PROMPT_EVAL_TOKENS).into());
+    }
+    let url = server_url.unwrap_or_else(get_ollama_url);
+
+    // A fresh nonce up front keeps Ollama from reusing a cached prefix from an earlier run
+    let mut prompt = uuid::Uuid::new_v4().to_string();
+    for word in FILLER_WORDS.iter().cycle().take(prompt_tokens) {
+        prompt.push(' ');
+        prompt.push_str(word);
+    }
+
+    let response = http
+        .client()
+        .post(format!("{}/api/generate", url))
+        .timeout(std::time::Duration::from_secs(10 * 60))
+        .json(&serde_json::json!({
+            "model": name,
+            "prompt": prompt,
+            "raw": true,
+            "stream": false,
+            // Room for the whole prompt, or the server silently truncates it
+            "options": { "num_predict": 1, "num_ctx": prompt_tokens + 64 }
+        }))
+        .send()
+        .await
+        ?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(OllieError::from_status(status, format!("HTTP error: {} {}", status, body.trim())));
+    }
+    let body: serde_json::Value = response.json().await.map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let count = body.get("prompt_eval_count").and_then(|c| c.as_u64()).unwrap_or(0);
+    let duration_ns = body.get("prompt_eval_duration").and_then(|d| d.as_u64()).unwrap_or(0);
+    if count == 0 || duration_ns == 0 {
+        return Err("Server reported no prompt evaluation timings".into());
+    }
+    Ok(PromptEvalSpeed {
+        requested_tokens: prompt_tokens,
+        prompt_eval_count: count,
+        prompt_eval_ms: duration_ns / 1_000_000,
+        tokens_per_sec: count as f64 / (duration_ns as f64 / 1e9),
+    })
+}
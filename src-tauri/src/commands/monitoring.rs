@@ -1,10 +1,15 @@
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, State};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::time;
 use sysinfo::System;
-use crate::commands::settings::get_ollama_url;
+use crate::commands::settings::{get_ollama_url, ollama_client_builder};
+use crate::commands::sys::wait_for_window_active;
+use crate::commands::error::OllieError;
+use crate::commands::models::HttpClient;
+use crate::commands::util::format_bytes;
+use crate::commands::events;
 
 // System metrics structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,7 +59,7 @@ pub async fn start_system_monitoring(
     app: AppHandle,
     interval_ms: Option<u64>,
     #[allow(non_snake_case)] intervalMs: Option<u64>,
-) -> Result<(), String> {
+) -> Result<(), OllieError> {
     if MONITORING_ACTIVE.load(Ordering::Relaxed) {
         return Ok(()); // Already monitoring
     }
@@ -70,6 +75,9 @@ pub async fn start_system_monitoring(
         
         while MONITORING_ACTIVE.load(Ordering::Relaxed) {
             interval.tick().await;
+            if wait_for_window_active().await {
+                interval.reset();
+            }
             
             // Refresh system information
             system.refresh_all();
@@ -78,13 +86,13 @@ pub async fn start_system_monitoring(
             let metrics = collect_system_metrics(&system);
             
             // Emit system metrics event
-            if let Err(e) = app.emit("monitoring:system-metrics", &metrics) {
+            if let Err(e) = app.emit(events::MONITORING_SYSTEM_METRICS, &metrics) {
                 eprintln!("Failed to emit system metrics: {}", e);
             }
             
             // Collect Ollama status
             if let Ok(ollama_status) = collect_ollama_status().await {
-                if let Err(e) = app.emit("monitoring:ollama-status", &ollama_status) {
+                if let Err(e) = app.emit(events::MONITORING_OLLAMA_STATUS, &ollama_status) {
                     eprintln!("Failed to emit Ollama status: {}", e);
                 }
             }
@@ -99,14 +107,14 @@ pub async fn start_system_monitoring(
 
 // Stop system monitoring
 #[tauri::command]
-pub async fn stop_system_monitoring() -> Result<(), String> {
+pub async fn stop_system_monitoring() -> Result<(), OllieError> {
     MONITORING_ACTIVE.store(false, Ordering::Relaxed);
     Ok(())
 }
 
 // Get current system metrics
 #[tauri::command]
-pub async fn get_system_metrics() -> Result<SystemMetrics, String> {
+pub async fn get_system_metrics() -> Result<SystemMetrics, OllieError> {
     let mut system = System::new_all();
     system.refresh_all();
     Ok(collect_system_metrics(&system))
@@ -114,7 +122,7 @@ pub async fn get_system_metrics() -> Result<SystemMetrics, String> {
 
 // Get model performance metrics
 #[tauri::command]
-pub async fn get_model_metrics(model_name: Option<String>) -> Result<Vec<ModelMetrics>, String> {
+pub async fn get_model_metrics(model_name: Option<String>) -> Result<Vec<ModelMetrics>, OllieError> {
     // This would typically query a database or monitoring system
     // For now, return mock data for demonstration
     let timestamp = SystemTime::now()
@@ -147,8 +155,8 @@ pub async fn get_model_metrics(model_name: Option<String>) -> Result<Vec<ModelMe
 
 // Get Ollama server status
 #[tauri::command]
-pub async fn get_ollama_status() -> Result<OllamaStatus, String> {
-    collect_ollama_status().await
+pub async fn get_ollama_status() -> Result<OllamaStatus, OllieError> {
+    collect_ollama_status().await.map_err(OllieError::from)
 }
 
 // Helper function to collect system metrics
@@ -196,7 +204,7 @@ async fn collect_ollama_status() -> Result<OllamaStatus, String> {
     let base_url = get_ollama_url();
     
     // Try to connect to Ollama API
-    let client = reqwest::Client::new();
+    let client = ollama_client_builder().build().map_err(|e| e.to_string())?;
     
     // Check if Ollama is running
     match client.get(format!("{}/api/version", base_url)).send().await {
@@ -266,7 +274,7 @@ pub fn track_model_performance(
         timestamp,
     };
     
-    if let Err(e) = app.emit("monitoring:model-metrics", &metrics) {
+    if let Err(e) = app.emit(events::MONITORING_MODEL_METRICS, &metrics) {
         eprintln!("Failed to emit model metrics: {}", e);
     }
 }
@@ -299,26 +307,114 @@ pub struct OllamaPsResponse {
 }
 
 #[tauri::command]
-pub async fn ollama_ps() -> Result<OllamaPsResponse, String> {
-    let base_url = get_ollama_url();
-    let client = reqwest::Client::new();
-    
-    match client.get(format!("{}/api/ps", base_url)).send().await {
-        Ok(response) => {
-            if response.status().is_success() {
-                response.json::<OllamaPsResponse>().await.map_err(|e| format!("Failed to parse response: {}", e))
-            } else {
-                Err(format!("Server returned status: {}", response.status()))
+pub async fn ollama_ps(http: State<'_, HttpClient>) -> Result<OllamaPsResponse, OllieError> {
+    fetch_ps(&http.client(), &get_ollama_url()).await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningModel {
+    pub name: String,
+    pub size: u64,
+    /// `size` formatted for display, e.g. "4.1 GB"
+    pub size_human: String,
+    pub size_vram: u64,
+    /// As the server reported it (RFC 3339)
+    pub expires_at: String,
+    /// `expires_at` as epoch millis, for a countdown; None if it didn't parse
+    pub expires_at_ms: Option<i64>,
+    /// Seconds until the model is unloaded, never negative
+    pub expires_in_secs: Option<i64>,
+}
+
+/// Models currently loaded in memory. Servers without `/api/ps` report none rather than failing.
+#[tauri::command]
+pub async fn models_ps(server_url: Option<String>, http: State<'_, HttpClient>) -> Result<Vec<RunningModel>, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let response = http.client().get(format!("{}/api/ps", url)).timeout(Duration::from_secs(10)).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(OllieError::from_status(response.status(), format!("Server returned status: {}", response.status())));
+    }
+    let ps = response.json::<OllamaPsResponse>().await?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    Ok(ps
+        .models
+        .into_iter()
+        .map(|m| {
+            let expires_at_ms = chrono::DateTime::parse_from_rfc3339(&m.expires_at).ok().map(|t| t.timestamp_millis());
+            RunningModel {
+                name: m.name,
+                size_human: format_bytes(i64::try_from(m.size).unwrap_or(i64::MAX)),
+                size: m.size,
+                size_vram: m.size_vram,
+                expires_in_secs: expires_at_ms.map(|at| ((at - now) / 1000).max(0)),
+                expires_at_ms,
+                expires_at: m.expires_at,
             }
-        }
-        Err(e) => Err(format!("Failed to connect to Ollama: {}", e)),
+        })
+        .collect())
+}
+
+pub async fn fetch_ps(client: &reqwest::Client, base_url: &str) -> Result<OllamaPsResponse, OllieError> {
+    let response = client.get(format!("{}/api/ps", base_url)).send().await?;
+    if !response.status().is_success() {
+        return Err(OllieError::from_status(response.status(), format!("Server returned status: {}", response.status())));
+    }
+    Ok(response.json::<OllamaPsResponse>().await?)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySample {
+    pub timestamp: u64,
+    /// False when the model was not in `/api/ps` at this tick (unloaded or not yet loaded)
+    pub loaded: bool,
+    pub size: u64,
+    pub size_vram: u64,
+}
+
+// `/api/ps` reports names with an explicit tag
+pub fn is_same_model(running: &OllamaProcess, name: &str) -> bool {
+    let tagged = if name.contains(':') { name.to_string() } else { format!("{}:latest", name) };
+    running.name == tagged || running.model == tagged
+}
+
+/// Poll `/api/ps` `samples` times, `interval_ms` apart, recording the model's footprint
+#[tauri::command]
+pub async fn model_memory_sample(
+    name: String,
+    server_url: Option<String>,
+    samples: Option<u32>,
+    interval_ms: Option<u64>,
+    http: State<'_, HttpClient>,
+) -> Result<Vec<MemorySample>, OllieError> {
+    let base_url = server_url.unwrap_or_else(get_ollama_url);
+    let count = samples.unwrap_or(10).clamp(1, 1000);
+    let mut interval = time::interval(Duration::from_millis(interval_ms.unwrap_or(1000).max(100)));
+
+    let mut out = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        interval.tick().await;
+        let ps = fetch_ps(&http.client(), &base_url).await?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let sample = match ps.models.iter().find(|m| is_same_model(m, &name)) {
+            Some(m) => MemorySample { timestamp, loaded: true, size: m.size, size_vram: m.size_vram },
+            None => MemorySample { timestamp, loaded: false, size: 0, size_vram: 0 },
+        };
+        out.push(sample);
     }
+    Ok(out)
 }
 
 #[tauri::command]
-pub async fn stop_model(name: String) -> Result<(), String> {
+pub async fn stop_model(name: String, http: State<'_, HttpClient>) -> Result<(), OllieError> {
     let base_url = get_ollama_url();
-    let client = reqwest::Client::new();
+    let client = http.client();
     
     // To stop a model, we send a generate request with keep_alive: 0
     // This unloads the model immediately
@@ -338,9 +434,9 @@ pub async fn stop_model(name: String) -> Result<(), String> {
             if response.status().is_success() {
                 Ok(())
             } else {
-                Err(format!("Server returned status: {}", response.status()))
+                Err(OllieError::from_status(response.status(), format!("Server returned status: {}", response.status())))
             }
         },
-        Err(e) => Err(format!("Failed to connect to Ollama: {}", e)),
+        Err(e) => Err(format!("Failed to connect to Ollama: {}", e).into()),
     }
 }
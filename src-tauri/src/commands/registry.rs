@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::commands::settings::http_client_builder;
+
+const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+
+/// A model name split the way Ollama resolves it against a registry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRef {
+    pub host: String,
+    pub namespace: String,
+    pub repo: String,
+    pub tag: String,
+}
+
+impl ModelRef {
+    /// Parse `llama3`, `llama3:8b`, `user/model:tag` or `host/ns/model:tag`
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Model name is empty".to_string());
+        }
+        let (path, tag) = match name.rsplit_once(':') {
+            Some((p, t)) if !t.contains('/') => (p, t.to_string()),
+            _ => (name, "latest".to_string()),
+        };
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let (host, namespace, repo) = match segments.as_slice() {
+            [] => return Err(format!("Invalid model name '{}'", name)),
+            [repo] => (DEFAULT_REGISTRY.to_string(), "library".to_string(), repo.to_string()),
+            [ns, repo] => (DEFAULT_REGISTRY.to_string(), ns.to_string(), repo.to_string()),
+            [host, ns @ .., repo] => (host.to_string(), ns.join("/"), repo.to_string()),
+        };
+        Ok(Self { host, namespace, repo, tag })
+    }
+
+    pub fn manifest_url(&self) -> String {
+        format!("https://{}/v2/{}/{}/manifests/{}", self.host, self.namespace, self.repo, self.tag)
+    }
+
+    pub fn blob_url(&self, digest: &str) -> String {
+        format!("https://{}/v2/{}/{}/blobs/{}", self.host, self.namespace, self.repo, digest)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestLayer {
+    #[serde(rename = "mediaType")]
+    pub media_type: String,
+    pub digest: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub config: Option<ManifestLayer>,
+    #[serde(default)]
+    pub layers: Vec<ManifestLayer>,
+}
+
+impl Manifest {
+    /// Config blob plus every layer
+    pub fn blobs(&self) -> impl Iterator<Item = &ManifestLayer> {
+        self.config.iter().chain(self.layers.iter())
+    }
+}
+
+pub fn registry_client() -> Result<reqwest::Client, String> {
+    http_client_builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Raw manifest bytes; Ollama stores them verbatim, so their sha256 is the local digest
+pub async fn fetch_manifest_bytes(client: &reqwest::Client, model: &ModelRef) -> Result<bytes::Bytes, String> {
+    let resp = client
+        .get(model.manifest_url())
+        .header("Accept", "application/vnd.docker.distribution.manifest.v2+json")
+        .send()
+        .await
+        .map_err(|e| format!("Registry request failed: {}", e))?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("Model '{}/{}:{}' not found in registry", model.namespace, model.repo, model.tag));
+    }
+    if !resp.status().is_success() {
+        return Err(format!("Registry returned status: {}", resp.status()));
+    }
+    resp.bytes().await.map_err(|e| format!("Failed to read manifest: {}", e))
+}
+
+pub async fn fetch_manifest(client: &reqwest::Client, model: &ModelRef) -> Result<Manifest, String> {
+    let bytes = fetch_manifest_bytes(client, model).await?;
+    serde_json::from_slice::<Manifest>(&bytes).map_err(|e| format!("Failed to parse manifest: {}", e))
+}
+
+#[derive(Debug, Deserialize)]
+struct TagList {
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// All tags published for the model's repository
+pub async fn fetch_tags(client: &reqwest::Client, model: &ModelRef) -> Result<Vec<String>, String> {
+    let url = format!("https://{}/v2/{}/{}/tags/list", model.host, model.namespace, model.repo);
+    let resp = client.get(&url).send().await.map_err(|e| format!("Registry request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Registry returned status: {}", resp.status()));
+    }
+    Ok(resp.json::<TagList>().await.map_err(|e| format!("Failed to parse tag list: {}", e))?.tags)
+}
+
+/// Quantization encoded in a tag suffix (`8b-instruct-q4_K_M` -> `Q4_K_M`), normalized to
+/// the spelling `/api/tags` uses in `quantization_level`
+pub fn quant_from_tag(tag: &str) -> Option<String> {
+    let suffix = tag.rsplit('-').next().unwrap_or(tag).to_lowercase();
+    let is_quant = match suffix.strip_prefix('q') {
+        Some(rest) => rest.starts_with(|c: char| c.is_ascii_digit()),
+        None => matches!(suffix.as_str(), "fp16" | "f16" | "bf16" | "fp32" | "f32"),
+    };
+    if !is_quant {
+        return None;
+    }
+    Some(normalize_quant(&suffix))
+}
+
+pub fn normalize_quant(quant: &str) -> String {
+    match quant.to_lowercase().as_str() {
+        "fp16" => "F16".to_string(),
+        "fp32" => "F32".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+const DIGEST_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+lazy_static::lazy_static! {
+    static ref DIGEST_CACHE: Mutex<HashMap<String, (Instant, String)>> = Mutex::new(HashMap::new());
+}
+
+/// Hex sha256 of the registry's current manifest for `model`, cached for a few minutes
+pub async fn remote_digest(client: &reqwest::Client, model: &ModelRef) -> Result<String, String> {
+    let key = model.manifest_url();
+    if let Some((at, digest)) = DIGEST_CACHE.lock().unwrap().get(&key) {
+        if at.elapsed() < DIGEST_CACHE_TTL {
+            return Ok(digest.clone());
+        }
+    }
+    let bytes = fetch_manifest_bytes(client, model).await?;
+    let digest = format!("{:x}", Sha256::digest(&bytes));
+    DIGEST_CACHE.lock().unwrap().insert(key, (Instant::now(), digest.clone()));
+    Ok(digest)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullSizeEstimate {
+    pub name: String,
+    pub total_bytes: u64,
+    pub present_bytes: u64,
+    pub remaining_bytes: u64,
+}
+
+/// Size a pull from the registry manifest, counting blobs already in `models_dir` as present
+pub async fn estimate_pull(client: &reqwest::Client, name: &str, models_dir: &Path) -> Result<PullSizeEstimate, String> {
+    let manifest = fetch_manifest(client, &ModelRef::parse(name)?).await?;
+    let blobs_dir = models_dir.join("blobs");
+    let mut total_bytes = 0;
+    let mut present_bytes = 0;
+    for blob in manifest.blobs() {
+        total_bytes += blob.size;
+        // Blobs are stored as sha256-<hex> on disk while manifests use sha256:<hex>
+        if blobs_dir.join(blob.digest.replace(':', "-")).exists() {
+            present_bytes += blob.size;
+        }
+    }
+    Ok(PullSizeEstimate {
+        name: name.to_string(),
+        total_bytes,
+        present_bytes,
+        remaining_bytes: total_bytes - present_bytes,
+    })
+}
+
+const PROBE_BYTES: u64 = 8 * 1024 * 1024;
+const PROBE_MAX_TIME: Duration = Duration::from_secs(5);
+
+/// Download throughput from the registry in bytes/sec, measured by fetching the start of the
+/// model's largest blob for at most a few seconds
+pub async fn probe_bandwidth(client: &reqwest::Client, model: &ModelRef) -> Result<f64, String> {
+    use futures_util::StreamExt;
+
+    let manifest = fetch_manifest(client, model).await?;
+    let blob = manifest.blobs().max_by_key(|b| b.size).ok_or("Manifest has no blobs")?;
+    let resp = client
+        .get(model.blob_url(&blob.digest))
+        .header("Range", format!("bytes=0-{}", PROBE_BYTES.min(blob.size).saturating_sub(1)))
+        .send()
+        .await
+        .map_err(|e| format!("Bandwidth probe failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Registry returned status: {}", resp.status()));
+    }
+
+    // Timed from the first response byte so connection setup doesn't count as slowness
+    let started = Instant::now();
+    let mut received = 0u64;
+    let mut body = resp.bytes_stream();
+    while received < PROBE_BYTES && started.elapsed() < PROBE_MAX_TIME {
+        match tokio::time::timeout(PROBE_MAX_TIME.saturating_sub(started.elapsed()), body.next()).await {
+            Ok(Some(chunk)) => received += chunk.map_err(|e| format!("Bandwidth probe failed: {}", e))?.len() as u64,
+            Ok(None) | Err(_) => break,
+        }
+    }
+    let secs = started.elapsed().as_secs_f64();
+    if received == 0 || secs <= 0.0 {
+        return Err("Bandwidth probe received no data".to_string());
+    }
+    Ok(received as f64 / secs)
+}
+
+// Frequently pulled library models, so typos resolve even with nothing installed yet
+const POPULAR_MODELS: &[&str] = &[
+    "llama2", "llama3", "llama3.1", "llama3.2", "llama3.3", "mistral", "mixtral", "gemma", "gemma2",
+    "gemma3", "qwen", "qwen2", "qwen2.5", "qwen2.5-coder", "qwen3", "phi3", "phi4", "deepseek-r1",
+    "deepseek-coder", "codellama", "llava", "nomic-embed-text", "mxbai-embed-large", "tinyllama",
+    "starcoder2", "command-r", "orca-mini", "vicuna", "neural-chat", "smollm2", "gpt-oss",
+];
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+fn short_name(model: &ModelRef) -> String {
+    if model.namespace == "library" {
+        model.repo.clone()
+    } else {
+        format!("{}/{}", model.namespace, model.repo)
+    }
+}
+
+/// Names close to `requested` among installed models, popular library models and, when the
+/// repository exists but the tag doesn't, that repository's tags. Closest first.
+pub async fn suggest_names(client: &reqwest::Client, requested: &str, installed: &[String]) -> Vec<String> {
+    let Ok(wanted) = ModelRef::parse(requested) else { return Vec::new() };
+    let wanted_name = short_name(&wanted);
+    let explicit_tag = requested.rsplit_once(':').is_some_and(|(_, t)| !t.contains('/'));
+    let max_distance = (wanted_name.chars().count() / 4).clamp(1, 3);
+
+    let mut known: Vec<String> = installed
+        .iter()
+        .filter_map(|n| ModelRef::parse(n).ok())
+        .map(|m| short_name(&m))
+        .chain(POPULAR_MODELS.iter().map(|s| s.to_string()))
+        .collect();
+    known.sort();
+    known.dedup();
+
+    let mut scored: Vec<(usize, String)> = Vec::new();
+    if known.contains(&wanted_name) {
+        // The repository is right, so the tag must be the typo
+        if let Ok(tags) = fetch_tags(client, &wanted).await {
+            let tag_distance = (wanted.tag.chars().count() / 4).clamp(1, 3);
+            scored.extend(
+                tags.into_iter()
+                    .map(|t| (edit_distance(&wanted.tag, &t), t))
+                    .filter(|(d, _)| *d > 0 && *d <= tag_distance)
+                    .map(|(d, t)| (d, format!("{}:{}", wanted_name, t))),
+            );
+        }
+    } else {
+        scored.extend(
+            known
+                .into_iter()
+                .map(|k| (edit_distance(&wanted_name, &k), k))
+                .filter(|(d, _)| *d <= max_distance)
+                .map(|(d, k)| (d, if explicit_tag { format!("{}:{}", k, wanted.tag) } else { k })),
+        );
+    }
+    scored.sort();
+    scored.into_iter().take(5).map(|(_, name)| name).collect()
+}
+
+const LIBRARY_SEARCH_URL: &str = "https://ollama.com/search";
+const SEARCH_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+lazy_static::lazy_static! {
+    static ref SEARCH_CACHE: Mutex<HashMap<String, (Instant, Vec<LibraryModel>)>> = Mutex::new(HashMap::new());
+}
+
+/// One entry of the ollama.com library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryModel {
+    pub name: String,
+    pub description: String,
+    /// Parameter-size tags offered, e.g. `["8b", "70b"]`
+    pub sizes: Vec<String>,
+    /// None when the listing didn't say (or the entry came from the bundled list)
+    pub pulls: Option<u64>,
+}
+
+/// Library models matching `query`, cached for a few minutes. ollama.com has no search API,
+/// so this reads the search page; if that fails the bundled popular list is filtered instead.
+pub async fn search_library(client: &reqwest::Client, query: &str) -> Vec<LibraryModel> {
+    let key = query.trim().to_lowercase();
+    if let Some((at, models)) = SEARCH_CACHE.lock().unwrap().get(&key) {
+        if at.elapsed() < SEARCH_CACHE_TTL {
+            return models.clone();
+        }
+    }
+    match fetch_search_page(client, &key).await {
+        Ok(models) => {
+            SEARCH_CACHE.lock().unwrap().insert(key, (Instant::now(), models.clone()));
+            models
+        }
+        Err(e) => {
+            eprintln!("Library search failed, using the bundled list: {}", e);
+            POPULAR_MODELS
+                .iter()
+                .filter(|name| name.contains(key.as_str()))
+                .map(|name| LibraryModel { name: name.to_string(), description: String::new(), sizes: Vec::new(), pulls: None })
+                .collect()
+        }
+    }
+}
+
+async fn fetch_search_page(client: &reqwest::Client, query: &str) -> Result<Vec<LibraryModel>, String> {
+    let resp = client
+        .get(LIBRARY_SEARCH_URL)
+        .query(&[("q", query)])
+        .send()
+        .await
+        .map_err(|e| format!("Library search failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Library search returned {}", resp.status()));
+    }
+    let html = resp.text().await.map_err(|e| e.to_string())?;
+    Ok(parse_search_page(&html))
+}
+
+// Each result is an `<li x-test-model>` whose fields carry `x-test-*` marker attributes
+fn parse_search_page(html: &str) -> Vec<LibraryModel> {
+    lazy_static::lazy_static! {
+        static ref TITLE: regex::Regex = regex::Regex::new(r"x-test-search-response-title[^>]*>([^<]+)<").unwrap();
+        static ref DESCRIPTION: regex::Regex = regex::Regex::new(r"<p[^>]*>([^<]+)</p>").unwrap();
+        static ref SIZE: regex::Regex = regex::Regex::new(r"x-test-size[^>]*>([^<]+)<").unwrap();
+        static ref PULLS: regex::Regex = regex::Regex::new(r"x-test-pull-count[^>]*>([^<]+)<").unwrap();
+    }
+    html.split("x-test-model")
+        .skip(1)
+        .filter_map(|entry| {
+            let name = decode_entities(TITLE.captures(entry)?[1].trim());
+            let description = DESCRIPTION.captures(entry).map(|c| decode_entities(c[1].trim())).unwrap_or_default();
+            let sizes = SIZE.captures_iter(entry).map(|c| c[1].trim().to_string()).collect();
+            let pulls = PULLS.captures(entry).and_then(|c| parse_count(c[1].trim()));
+            Some(LibraryModel { name, description, sizes, pulls })
+        })
+        .collect()
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"").replace("&#39;", "'").replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+/// "1.2M", "530K" or "12,345" as a number
+fn parse_count(text: &str) -> Option<u64> {
+    let text = text.replace(',', "");
+    let (number, scale) = match text.chars().last()?.to_ascii_uppercase() {
+        'K' => (&text[..text.len() - 1], 1e3),
+        'M' => (&text[..text.len() - 1], 1e6),
+        'B' => (&text[..text.len() - 1], 1e9),
+        _ => (text.as_str(), 1.0),
+    };
+    number.parse::<f64>().ok().map(|n| (n * scale).round() as u64)
+}
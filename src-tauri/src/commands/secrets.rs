@@ -0,0 +1,43 @@
+// Provider and server API keys live in the OS keychain; settings.json only keeps a `keyring:<account>`
+// reference. Where no keychain is available the key stays in the file as before.
+
+const SERVICE: &str = "ollie";
+const REFERENCE_PREFIX: &str = "keyring:";
+
+pub fn provider_account(provider_id: &str) -> String {
+    format!("provider:{}", provider_id)
+}
+
+pub fn server_account(profile_name: &str) -> String {
+    format!("server:{}", profile_name)
+}
+
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(REFERENCE_PREFIX)
+}
+
+/// Move `secret` into the keychain and return the reference to store in its place
+pub fn store(account: &str, secret: &str) -> Result<String, String> {
+    let entry = keyring::Entry::new(SERVICE, account).map_err(|e| e.to_string())?;
+    entry.set_password(secret).map_err(|e| e.to_string())?;
+    Ok(format!("{}{}", REFERENCE_PREFIX, account))
+}
+
+/// The secret a reference points at; None when it is missing or the keychain is unavailable
+pub fn resolve(reference: &str) -> Option<String> {
+    let account = reference.strip_prefix(REFERENCE_PREFIX)?;
+    let entry = keyring::Entry::new(SERVICE, account).ok()?;
+    match entry.get_password() {
+        Ok(secret) => Some(secret),
+        Err(e) => {
+            eprintln!("Could not read {} from the keychain: {}", account, e);
+            None
+        }
+    }
+}
+
+pub fn delete(account: &str) {
+    if let Ok(entry) = keyring::Entry::new(SERVICE, account) {
+        let _ = entry.delete_credential();
+    }
+}
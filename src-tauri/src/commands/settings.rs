@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use crate::providers::ProviderConfig;
+use tauri::{Emitter, Manager};
+use crate::commands::secrets;
+use crate::commands::sys::{ensure_writable, ollama_host_url};
+use crate::providers::{KeepAlive, ProviderConfig};
+use crate::commands::error::OllieError;
+use crate::commands::events;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct DefaultParams {
     pub temperature: Option<f64>,
     pub top_k: Option<i32>,
@@ -11,9 +16,77 @@ pub struct DefaultParams {
     pub max_tokens: Option<i32>,
 }
 
+impl DefaultParams {
+    /// Fields set in `overlay` win; unset ones fall through to `self`
+    pub fn merge(&self, overlay: &DefaultParams) -> DefaultParams {
+        DefaultParams {
+            temperature: overlay.temperature.or(self.temperature),
+            top_k: overlay.top_k.or(self.top_k),
+            top_p: overlay.top_p.or(self.top_p),
+            max_tokens: overlay.max_tokens.or(self.max_tokens),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn params_merge(base: DefaultParams, overlay: DefaultParams) -> DefaultParams {
+    base.merge(&overlay)
+}
+
+/// Schema version written to settings.json; bump it and add a step to `migrate_settings`
+/// whenever a field is renamed, removed or changes meaning
+pub const SETTINGS_VERSION: u32 = 2;
+
+const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// A named Ollama server to switch between
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ServerProfile {
+    pub name: String,
+    pub url: String,
+    /// Bearer token for servers behind an authenticating proxy
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Extra headers sent with every request, e.g. `X-API-Key` for proxies that don't take a bearer token
+    #[serde(default)]
+    pub headers: std::collections::HashMap<String, String>,
+}
+
+// Header values are as likely to be credentials as the key itself
+impl std::fmt::Debug for ServerProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: std::collections::HashMap<&str, String> =
+            self.headers.iter().map(|(name, value)| (name.as_str(), mask_secret(value))).collect();
+        f.debug_struct("ServerProfile")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("api_key", &self.api_key.as_deref().map(mask_secret))
+            .field("headers", &headers)
+            .finish()
+    }
+}
+
+/// Enough of a secret to tell keys apart in a log line: `****abcd`
+pub fn mask_secret(secret: &str) -> String {
+    if secret.chars().count() <= 8 {
+        return "****".to_string();
+    }
+    let tail: String = secret.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    format!("****{}", tail)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Settings {
+    /// Schema version; files from before versioning read as 0
+    #[serde(default)]
+    pub version: u32,
+    /// URL of the active server profile, kept for code and frontends that predate profiles
     pub server_url: String,
+    #[serde(default)]
+    pub servers: Vec<ServerProfile>,
+    /// Name of the profile in `servers` that requests go to
+    #[serde(default)]
+    pub active_server: Option<String>,
     pub default_model: Option<String>,
     pub default_params: Option<DefaultParams>,
     pub theme: Option<String>,
@@ -27,6 +100,100 @@ pub struct Settings {
     /// Whether initial setup wizard has been completed
     #[serde(default)]
     pub setup_completed: bool,
+    /// keep_alive sent with Ollama requests that don't set their own
+    #[serde(default)]
+    pub default_keep_alive: Option<KeepAlive>,
+    /// Parallel `/api/show` requests when enriching the model list (default 4)
+    #[serde(default)]
+    pub show_concurrency: Option<usize>,
+    /// Cap on characters in one chat response before it is cut off (default 1,000,000)
+    #[serde(default)]
+    pub max_response_chars: Option<usize>,
+    /// Standby server tried once when the primary can't be reached
+    #[serde(default)]
+    pub fallback_server_url: Option<String>,
+    /// Pulls allowed to download at once; the rest wait in a queue (default 2)
+    #[serde(default)]
+    pub max_concurrent_pulls: Option<usize>,
+    /// Per model, regexes whose matches are removed from the final `chat_send` reply
+    #[serde(default)]
+    pub strip_patterns: std::collections::HashMap<String, Vec<String>>,
+    /// Stop a pull early when the disk can't hold it (default on)
+    #[serde(default)]
+    pub pull_disk_check: Option<bool>,
+    /// Directory whose disk the pull checks look at, for models stored somewhere Ollie
+    /// can't work out on its own (e.g. a remote server's share mounted locally)
+    #[serde(default)]
+    pub pull_disk_check_path: Option<String>,
+    /// Proxy for plain-http requests; empty falls back to `HTTP_PROXY`
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+    /// Proxy for https requests; empty falls back to `HTTPS_PROXY`
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts that bypass the proxy; empty falls back to `NO_PROXY`
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+    /// Per-endpoint request timeouts; read on every command, so edits apply without a restart
+    #[serde(default)]
+    pub timeouts: Timeouts,
+}
+
+/// Request timeouts in seconds; unset fields keep the built-in defaults
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Timeouts {
+    /// `models_list` (default 10)
+    pub list: Option<u64>,
+    /// `model_show` (default 30)
+    pub show: Option<u64>,
+    /// `model_delete` (default 60)
+    pub delete: Option<u64>,
+    /// `model_pull`, for the whole download (default 3600); 0 means no timeout
+    pub pull: Option<u64>,
+}
+
+pub const DEFAULT_LIST_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_SHOW_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_DELETE_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_PULL_TIMEOUT_SECS: u64 = 60 * 60;
+
+impl Timeouts {
+    pub fn list(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.list.unwrap_or(DEFAULT_LIST_TIMEOUT_SECS).max(1))
+    }
+
+    pub fn show(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.show.unwrap_or(DEFAULT_SHOW_TIMEOUT_SECS).max(1))
+    }
+
+    pub fn delete(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.delete.unwrap_or(DEFAULT_DELETE_TIMEOUT_SECS).max(1))
+    }
+
+    /// None when the pull may run as long as it takes
+    pub fn pull(&self) -> Option<std::time::Duration> {
+        match self.pull.unwrap_or(DEFAULT_PULL_TIMEOUT_SECS) {
+            0 => None,
+            secs => Some(std::time::Duration::from_secs(secs)),
+        }
+    }
+}
+
+impl Settings {
+    pub fn active_profile(&self) -> Option<&ServerProfile> {
+        let name = self.active_server.as_deref()?;
+        self.servers.iter().find(|p| p.name == name)
+    }
+
+    fn active_profile_mut(&mut self) -> Option<&mut ServerProfile> {
+        let name = self.active_server.clone()?;
+        self.servers.iter_mut().find(|p| p.name == name)
+    }
+
+    /// The URL requests should go to: the active profile's, else the plain `server_url`
+    pub fn active_server_url(&self) -> &str {
+        self.active_profile().map_or(self.server_url.as_str(), |p| p.url.as_str())
+    }
 }
 
 fn default_app_mode() -> String {
@@ -34,9 +201,26 @@ fn default_app_mode() -> String {
 }
 
 
+/// `ollie` under the platform config dir: `$XDG_CONFIG_HOME` or `~/.config` on Linux,
+/// `~/Library/Application Support` on macOS, `%APPDATA%` on Windows
+pub(crate) fn config_dir_path() -> Result<PathBuf, OllieError> {
+    if let Some(dir) = dirs::config_dir() {
+        return Ok(dir.join("ollie"));
+    }
+    let home = std::env::var("HOME").map_err(|e| format!("Cannot locate a config directory: {}", e))?;
+    Ok(PathBuf::from(home).join(".config").join("ollie"))
+}
+
+/// Where settings lived before the platform config dir was used; only set when it differs
+fn legacy_settings_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    let legacy = PathBuf::from(home).join(".config").join("ollie").join("settings.json");
+    let current = config_dir_path().ok()?.join("settings.json");
+    (legacy != current).then_some(legacy)
+}
+
 fn config_dir() -> Result<PathBuf, String> {
-    let home = std::env::var("HOME").map_err(|e| format!("Cannot read HOME: {}", e))?;
-    let dir = PathBuf::from(home).join(".config").join("ollie");
+    let dir = config_dir_path()?;
     if !dir.exists() {
         fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
     }
@@ -45,15 +229,25 @@ fn config_dir() -> Result<PathBuf, String> {
 
 fn settings_path() -> Result<PathBuf, String> { Ok(config_dir()?.join("settings.json")) }
 
+/// The file to read settings from: the current location, or the legacy one until the
+/// first save migrates it
+fn settings_read_path() -> Result<PathBuf, String> {
+    let path = settings_path()?;
+    if path.exists() {
+        return Ok(path);
+    }
+    Ok(legacy_settings_path().filter(|p| p.exists()).unwrap_or(path))
+}
+
 /// Get the configured Ollama server URL (for use by other modules)
 pub fn get_ollama_url() -> String {
-    let path = match settings_path() {
+    let path = match settings_read_path() {
         Ok(p) => p,
         Err(_) => return "http://localhost:11434".to_string(),
     };
     
     if !path.exists() {
-        return "http://localhost:11434".to_string();
+        return default_settings().server_url;
     }
     
     let content = match fs::read_to_string(&path) {
@@ -66,124 +260,925 @@ pub fn get_ollama_url() -> String {
         Err(_) => return "http://localhost:11434".to_string(),
     };
     
-    if settings.server_url.is_empty() {
+    // Older saves could end in a slash, which would double up in `{url}/api/...`
+    let url = settings.active_server_url().trim().trim_end_matches('/');
+    if url.is_empty() {
         "http://localhost:11434".to_string()
     } else {
-        settings.server_url
+        url.to_string()
     }
 }
 
+/// The configured standby server, if any
+pub fn get_fallback_url() -> Option<String> {
+    let content = fs::read_to_string(settings_read_path().ok()?).ok()?;
+    let settings: Settings = serde_json::from_str(&content).ok()?;
+    settings.fallback_server_url.map(|url| url.trim().trim_end_matches('/').to_string()).filter(|url| !url.is_empty())
+}
+
+// A settings value if one is set, else the upper- or lower-case environment variable
+fn proxy_setting(value: Option<String>, var: &str) -> Option<String> {
+    value
+        .or_else(|| std::env::var(var).ok())
+        .or_else(|| std::env::var(var.to_lowercase()).ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// A client builder with the configured proxies applied. Without any proxy settings reqwest
+/// reads the environment itself; once one is set, the others fall back to their variables.
+/// `settings_set` rejects malformed proxy URLs, so one that slips in by hand is skipped.
+pub fn http_client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+    let settings: Option<Settings> = settings_read_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let Some(settings) = settings else { return builder };
+    let is_set = |v: &Option<String>| v.as_deref().is_some_and(|v| !v.trim().is_empty());
+    if !is_set(&settings.http_proxy) && !is_set(&settings.https_proxy) && !is_set(&settings.no_proxy) {
+        return builder;
+    }
+
+    let no_proxy = proxy_setting(settings.no_proxy, "NO_PROXY").and_then(|v| reqwest::NoProxy::from_string(&v));
+    let proxies = [
+        (proxy_setting(settings.http_proxy, "HTTP_PROXY"), reqwest::Proxy::http::<String> as fn(String) -> reqwest::Result<reqwest::Proxy>),
+        (proxy_setting(settings.https_proxy, "HTTPS_PROXY"), reqwest::Proxy::https::<String>),
+    ];
+    let mut builder = builder;
+    for (url, make) in proxies {
+        let Some(url) = url else { continue };
+        match make(url.clone()) {
+            Ok(proxy) => builder = builder.proxy(proxy.no_proxy(no_proxy.clone())),
+            Err(e) => eprintln!("Ignoring proxy '{}': {}", url, e),
+        }
+    }
+    builder
+}
+
+/// `http_client_builder` plus the active profile's bearer token and headers, for requests
+/// to Ollama. Registry and other third-party requests must not use it.
+pub fn ollama_client_builder() -> reqwest::ClientBuilder {
+    let builder = http_client_builder();
+    let profile = settings_read_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Settings>(&content).ok())
+        .and_then(|settings| settings.active_profile().cloned());
+    let Some(profile) = profile else { return builder };
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in &profile.headers {
+        let header = reqwest::header::HeaderName::from_bytes(name.trim().as_bytes())
+            .ok()
+            .zip(reqwest::header::HeaderValue::from_str(value.trim()).ok());
+        match header {
+            Some((name, mut value)) => {
+                value.set_sensitive(true);
+                headers.insert(name, value);
+            }
+            None => eprintln!("Ignoring invalid header '{}: {}' on server '{}'", name, mask_secret(value), profile.name),
+        }
+    }
+    let key = profile.api_key.filter(|k| !k.is_empty()).and_then(|k| if secrets::is_reference(&k) { secrets::resolve(&k) } else { Some(k) });
+    if let Some(key) = key {
+        match reqwest::header::HeaderValue::from_str(&format!("Bearer {}", key.trim())) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+            Err(_) => eprintln!("Ignoring invalid API key {} on server '{}'", mask_secret(&key), profile.name),
+        }
+    }
+    builder.default_headers(headers)
+}
+
+static SETTINGS_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Bumped on every settings write, so cached clients know to rebuild
+pub fn settings_generation() -> u64 {
+    SETTINGS_GENERATION.load(std::sync::atomic::Ordering::Acquire)
+}
+
 fn default_providers() -> Vec<ProviderConfig> {
     vec![ProviderConfig::ollama_default()]
 }
 
+fn default_settings() -> Settings {
+    // First run follows an existing Ollama setup
+    let server_url = ollama_host_url().unwrap_or_else(|| "http://localhost:11434".to_string());
+    Settings {
+        version: SETTINGS_VERSION,
+        servers: vec![ServerProfile { name: DEFAULT_PROFILE_NAME.to_string(), url: server_url.clone(), api_key: None, headers: Default::default() }],
+        active_server: Some(DEFAULT_PROFILE_NAME.to_string()),
+        server_url,
+        default_model: None,
+        default_params: None,
+        theme: Some("light".to_string()),
+        providers: default_providers(),
+        active_provider_id: Some("ollama-default".to_string()),
+        app_mode: "local".to_string(),
+        setup_completed: false,
+        default_keep_alive: None,
+        show_concurrency: None,
+        max_response_chars: None,
+        fallback_server_url: None,
+        max_concurrent_pulls: None,
+        strip_patterns: Default::default(),
+        pull_disk_check: None,
+        pull_disk_check_path: None,
+        http_proxy: None,
+        https_proxy: None,
+        no_proxy: None,
+        timeouts: Timeouts::default(),
+    }
+}
+
+const SETTINGS_READ_ATTEMPTS: u32 = 3;
+const SETTINGS_READ_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Read the settings file, retrying I/O errors a few times so a flaky filesystem at startup
+/// (network home directories) doesn't surface as missing settings
+async fn read_settings_with_retry(path: &std::path::Path) -> Result<String, String> {
+    let mut attempt = 1;
+    loop {
+        match fs::read_to_string(path) {
+            Ok(content) => return Ok(content),
+            Err(e) if attempt < SETTINGS_READ_ATTEMPTS => {
+                eprintln!("Settings read attempt {} failed: {}", attempt, e);
+                attempt += 1;
+                tokio::time::sleep(SETTINGS_READ_RETRY_DELAY).await;
+            }
+            Err(e) => return Err(format!("Failed to read settings after {} attempts: {}", attempt, e)),
+        }
+    }
+}
+
+fn stored_version(raw: &serde_json::Value) -> u32 {
+    raw.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32
+}
+
+/// Upgrade raw settings one version at a time. Files from a newer app are returned untouched.
+fn migrate_settings(mut raw: serde_json::Value) -> serde_json::Value {
+    let mut version = stored_version(&raw);
+    while version < SETTINGS_VERSION {
+        // Each arm turns `version` into `version + 1`
+        match version {
+            // 0 -> 1: unversioned files only gain the version field
+            0 => {}
+            // 1 -> 2: the single server_url becomes the "Default" profile
+            1 => {
+                if let serde_json::Value::Object(map) = &mut raw {
+                    let has_profiles = map.get("servers").and_then(|s| s.as_array()).is_some_and(|s| !s.is_empty());
+                    if !has_profiles {
+                        let url = map.get("server_url").cloned().unwrap_or_else(|| "http://localhost:11434".into());
+                        map.insert("servers".to_string(), serde_json::json!([{ "name": DEFAULT_PROFILE_NAME, "url": url }]));
+                        map.insert("active_server".to_string(), DEFAULT_PROFILE_NAME.into());
+                    }
+                }
+            }
+            _ => unreachable!("no migration from settings version {}", version),
+        }
+        version += 1;
+        if let serde_json::Value::Object(map) = &mut raw {
+            map.insert("version".to_string(), version.into());
+        }
+    }
+    raw
+}
+
+fn newer_version_error(version: u32) -> String {
+    format!(
+        "settings.json is from a newer version of Ollie (schema {}, this build supports {}); update Ollie instead of overwriting it",
+        version, SETTINGS_VERSION
+    )
+}
+
 #[tauri::command]
-pub async fn settings_get() -> Result<Settings, String> {
-    let path = settings_path()?;
-    if !path.exists() {
-        return Ok(Settings {
-            server_url: "http://localhost:11434".to_string(),
-            default_model: None,
-            default_params: None,
-            theme: Some("light".to_string()),
-            providers: default_providers(),
-            active_provider_id: Some("ollama-default".to_string()),
-            app_mode: "local".to_string(),
-            setup_completed: false,
-        });
+pub async fn settings_get() -> Result<Settings, OllieError> {
+    let mut settings = stored_settings().await?;
 
+    // Swap keychain references back for the keys themselves
+    let keys = settings.providers.iter_mut().map(|p| &mut p.api_key).chain(settings.servers.iter_mut().map(|s| &mut s.api_key));
+    for api_key in keys {
+        // An unreadable reference is kept as is so the next save doesn't drop it
+        if let Some(secret) = api_key.as_deref().filter(|k| secrets::is_reference(k)).and_then(secrets::resolve) {
+            *api_key = Some(secret);
+        }
     }
-    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read settings: {}", e))?;
-    let mut settings: Settings = serde_json::from_str(&content).map_err(|e| format!("Invalid settings JSON: {}", e))?;
+    Ok(settings)
+}
+
+/// Settings as saved, with API keys left as their `keyring:` references
+pub async fn stored_settings() -> Result<Settings, OllieError> {
+    let path = settings_read_path()?;
+    if !path.exists() {
+        return Ok(default_settings());
+    }
+    let content = read_settings_with_retry(&path).await?;
+    let raw = match serde_json::from_str::<serde_json::Value>(&content) {
+        Ok(raw) => migrate_settings(raw),
+        Err(e) => {
+            // Keep the broken file for recovery and carry on with defaults rather than failing every caller
+            let backup = path.with_extension("json.bak");
+            eprintln!("Invalid settings JSON ({}); moving it to {}", e, backup.display());
+            fs::rename(&path, &backup).map_err(|e| format!("Failed to back up corrupt settings: {}", e))?;
+            return Ok(default_settings());
+        }
+    };
+    let version = stored_version(&raw);
+    let mut settings: Settings = serde_json::from_value(raw).map_err(|e| {
+        if version > SETTINGS_VERSION { newer_version_error(version) } else { format!("Invalid settings JSON: {}", e) }
+    })?;
     
     // Ensure default providers exist
     if settings.providers.is_empty() {
         settings.providers = default_providers();
         settings.active_provider_id = Some("ollama-default".to_string());
     }
+    if let Some(url) = settings.active_profile().map(|p| p.url.clone()) {
+        settings.server_url = url;
+    }
     
     Ok(settings)
 }
 
+/// Read one top-level setting without building the whole `Settings` value, falling back
+/// to the default when the file or key is missing
 #[tauri::command]
-pub async fn settings_set(settings: Settings) -> Result<Settings, String> {
+pub async fn settings_get_field(key: String) -> Result<serde_json::Value, OllieError> {
+    let defaults = serde_json::to_value(default_settings())?;
+    let default = defaults
+        .get(&key)
+        .cloned()
+        .ok_or_else(|| format!("Unknown setting '{}'", key))?;
+
+    let path = settings_read_path()?;
+    if !path.exists() {
+        return Ok(default);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read settings: {}", e))?;
+    let raw: serde_json::Value = serde_json::from_str(&content).map_err(|e| format!("Invalid settings JSON: {}", e))?;
+    let serde_json::Value::Object(mut raw) = migrate_settings(raw) else {
+        return Err("Invalid settings JSON: not an object".into());
+    };
+    Ok(raw.remove(&key).unwrap_or(default))
+}
+
+/// Save the keys the caller sent over the stored settings. Keys it leaves out keep their
+/// stored values, so backend-only settings survive a save from an older frontend.
+#[tauri::command]
+pub async fn settings_set(settings: serde_json::Value) -> Result<Settings, OllieError> {
+    let serde_json::Value::Object(incoming) = settings else {
+        return Err("Settings must be an object".into());
+    };
+    // A frontend that only knows `server_url` is editing the active profile's URL
+    let url_edit = incoming.contains_key("server_url") && !incoming.contains_key("servers") && !incoming.contains_key("active_server");
+    let mut merged = serde_json::to_value(settings_get().await?)?;
+    if let serde_json::Value::Object(stored) = &mut merged {
+        stored.extend(incoming);
+    }
+    let mut settings: Settings = serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+    normalize_settings(&mut settings)?;
+    if url_edit {
+        let url = settings.server_url.clone();
+        if let Some(profile) = settings.active_profile_mut() {
+            profile.url = url;
+        }
+    }
+    save_settings(settings)
+}
+
+/// Normalize every server URL and reject proxies reqwest can't parse; run on anything
+/// that replaces the stored settings
+pub fn normalize_settings(settings: &mut Settings) -> Result<(), String> {
+    settings.server_url = normalize_server_url(&settings.server_url)?;
+    if let Some(url) = settings.fallback_server_url.as_deref().filter(|u| !u.trim().is_empty()) {
+        settings.fallback_server_url = Some(normalize_server_url(url)?);
+    }
+    for profile in &mut settings.servers {
+        profile.url = normalize_server_url(&profile.url)?;
+    }
+    for (key, proxy) in [("http_proxy", &settings.http_proxy), ("https_proxy", &settings.https_proxy)] {
+        if let Some(url) = proxy.as_deref().filter(|u| !u.trim().is_empty()) {
+            reqwest::Proxy::all(url.trim()).map_err(|e| format!("Invalid {} '{}': {}", key, url, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Require an http(s) URL and drop trailing slashes so `{url}/api/...` joins cleanly
+pub fn normalize_server_url(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    // Without a scheme, `localhost:11434` parses with "localhost" as the scheme
+    match reqwest::Url::parse(trimmed) {
+        Ok(u) if (u.scheme() == "http" || u.scheme() == "https") && u.has_host() => Ok(trimmed.to_string()),
+        Ok(_) => Err(format!("Server URL '{}' must start with http:// or https://", raw)),
+        Err(e) => Err(format!("Invalid server URL '{}': {}", raw, e)),
+    }
+}
+
+pub fn save_settings(mut settings: Settings) -> Result<Settings, OllieError> {
+    ensure_writable("settings_set")?;
+    if let Some(url) = settings.active_profile().map(|p| p.url.clone()) {
+        settings.server_url = url;
+    }
+    if let Some(keep_alive) = &settings.default_keep_alive {
+        keep_alive.validate()?;
+    }
+    write_settings(&settings)?;
+    Ok(settings)
+}
+
+// Write to a sibling temp file and rename over settings.json so a crash never leaves it truncated
+fn write_settings(settings: &Settings) -> Result<(), String> {
     let path = settings_path()?;
+    let tmp = path.with_extension("json.tmp");
+    // A newer app wrote this file; saving would drop whatever it added
+    if let Some(on_disk) = fs::read_to_string(&path).ok().and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok()) {
+        let version = stored_version(&on_disk);
+        if version > SETTINGS_VERSION {
+            return Err(newer_version_error(version));
+        }
+    }
+    let mut settings = settings.clone();
+    settings.version = SETTINGS_VERSION;
+    let keys = settings
+        .providers
+        .iter_mut()
+        .map(|p| (secrets::provider_account(&p.id), &mut p.api_key))
+        .chain(settings.servers.iter_mut().map(|s| (secrets::server_account(&s.name), &mut s.api_key)));
+    for (account, api_key) in keys {
+        let Some(key) = api_key.as_deref().filter(|k| !k.is_empty() && !secrets::is_reference(k)) else {
+            continue;
+        };
+        match secrets::store(&account, key) {
+            Ok(reference) => *api_key = Some(reference),
+            Err(e) => eprintln!("No keychain available ({}); the API key for '{}' is stored in plain text", e, account),
+        }
+    }
     let content = serde_json::to_string_pretty(&settings).map_err(|e| format!("Serialize settings failed: {}", e))?;
-    fs::write(&path, content).map_err(|e| format!("Failed to write settings: {}", e))?;
-    Ok(settings)
+    // Flush before the rename so a full disk fails here instead of leaving an empty file behind
+    let written = fs::File::create(&tmp).and_then(|mut f| {
+        std::io::Write::write_all(&mut f, content.as_bytes())?;
+        f.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("Failed to write settings: {}", e));
+    }
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to replace settings: {}", e))?;
+    SETTINGS_GENERATION.fetch_add(1, std::sync::atomic::Ordering::Release);
+    Ok(())
+}
+
+const THEMES: [&str; 3] = ["light", "dark", "system"];
+
+pub const DEFAULT_SHOW_CONCURRENCY: usize = 4;
+pub const MAX_SHOW_CONCURRENCY: usize = 32;
+
+pub const DEFAULT_MAX_CONCURRENT_PULLS: usize = 2;
+pub const MAX_CONCURRENT_PULLS: usize = 8;
+
+pub const DEFAULT_MAX_RESPONSE_CHARS: usize = 1_000_000;
+
+pub async fn max_response_chars() -> usize {
+    settings_get()
+        .await
+        .ok()
+        .and_then(|s| s.max_response_chars)
+        .unwrap_or(DEFAULT_MAX_RESPONSE_CHARS)
+}
+
+/// Configured `show_concurrency`, clamped to a sane range
+pub async fn show_concurrency() -> usize {
+    settings_get()
+        .await
+        .ok()
+        .and_then(|s| s.show_concurrency)
+        .unwrap_or(DEFAULT_SHOW_CONCURRENCY)
+        .clamp(1, MAX_SHOW_CONCURRENCY)
+}
+
+/// Configured request timeouts. Read on every call, so a change applies to the next
+/// request without a restart.
+pub async fn timeouts() -> Timeouts {
+    settings_get().await.map(|s| s.timeouts).unwrap_or_default()
+}
+
+/// Configured `max_concurrent_pulls`, clamped to a sane range
+pub async fn max_concurrent_pulls() -> usize {
+    settings_get()
+        .await
+        .ok()
+        .and_then(|s| s.max_concurrent_pulls)
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_PULLS)
+        .clamp(1, MAX_CONCURRENT_PULLS)
+}
+
+/// Compiled `strip_patterns` for `model`; a bare name also matches its `:latest` entry
+pub async fn strip_patterns_for(model: &str) -> Result<Vec<regex::Regex>, String> {
+    let Ok(settings) = settings_get().await else {
+        return Ok(Vec::new());
+    };
+    let bare = model.strip_suffix(":latest").unwrap_or(model);
+    let latest = format!("{}:latest", bare);
+    settings
+        .strip_patterns
+        .iter()
+        .filter(|(key, _)| *key == bare || **key == latest)
+        .flat_map(|(_, patterns)| patterns)
+        .map(|p| regex::Regex::new(p).map_err(|e| format!("Invalid strip pattern '{}' for {}: {}", p, model, e)))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn theme_get() -> Result<String, OllieError> {
+    let settings = settings_get().await?;
+    Ok(settings.theme.unwrap_or_else(|| "light".to_string()))
 }
 
 #[tauri::command]
-pub async fn provider_add(config: ProviderConfig) -> Result<Vec<ProviderConfig>, String> {
+pub async fn theme_set(app: tauri::AppHandle, theme: String) -> Result<String, OllieError> {
+    if !THEMES.contains(&theme.as_str()) {
+        return Err(format!("Unknown theme '{}', expected one of: {}", theme, THEMES.join(", ")).into());
+    }
+    let mut settings = settings_get().await?;
+    settings.theme = Some(theme.clone());
+    save_settings(settings)?;
+    emit_resolved_theme(&app).await;
+    Ok(theme)
+}
+
+/// The effective light/dark theme: the stored value, or the OS appearance when it is "system"
+#[tauri::command]
+pub async fn theme_resolved(app: tauri::AppHandle) -> Result<String, OllieError> {
+    let theme = theme_get().await?;
+    Ok(resolve_theme(&app, &theme))
+}
+
+fn resolve_theme(app: &tauri::AppHandle, theme: &str) -> String {
+    if theme != "system" {
+        return theme.to_string();
+    }
+    let os_theme = app
+        .get_webview_window("main")
+        .and_then(|w| w.theme().ok());
+    match os_theme {
+        Some(tauri::Theme::Dark) => "dark".to_string(),
+        _ => "light".to_string(),
+    }
+}
+
+/// Emit `theme:resolved`; called when the setting changes and when the OS theme flips
+pub async fn emit_resolved_theme(app: &tauri::AppHandle) {
+    let theme = theme_get().await.unwrap_or_else(|_| "light".to_string());
+    let resolved = resolve_theme(app, &theme);
+    let _ = app.emit(events::THEME_RESOLVED, &serde_json::json!({ "theme": theme, "resolved": resolved }));
+}
+
+#[tauri::command]
+pub async fn provider_add(config: ProviderConfig) -> Result<Vec<ProviderConfig>, OllieError> {
     let mut settings = settings_get().await?;
     
     // Check for duplicate ID
     if settings.providers.iter().any(|p| p.id == config.id) {
-        return Err(format!("Provider with ID '{}' already exists", config.id));
+        return Err(format!("Provider with ID '{}' already exists", config.id).into());
     }
     
     settings.providers.push(config);
-    settings_set(settings.clone()).await?;
+    save_settings(settings.clone())?;
     Ok(settings.providers)
 }
 
 #[tauri::command]
-pub async fn provider_update(config: ProviderConfig) -> Result<Vec<ProviderConfig>, String> {
+pub async fn provider_update(config: ProviderConfig) -> Result<Vec<ProviderConfig>, OllieError> {
     let mut settings = settings_get().await?;
     
     if let Some(pos) = settings.providers.iter().position(|p| p.id == config.id) {
         settings.providers[pos] = config;
-        settings_set(settings.clone()).await?;
+        save_settings(settings.clone())?;
         Ok(settings.providers)
     } else {
-        Err(format!("Provider with ID '{}' not found", config.id))
+        Err(OllieError::not_found(format!("Provider with ID '{}' not found", config.id)))
     }
 }
 
 #[tauri::command]
-pub async fn provider_delete(id: String) -> Result<Vec<ProviderConfig>, String> {
+pub async fn provider_delete(id: String) -> Result<Vec<ProviderConfig>, OllieError> {
     let mut settings = settings_get().await?;
     
     // Prevent deleting the default Ollama provider
     if id == "ollama-default" {
-        return Err("Cannot delete the default Ollama provider".to_string());
+        return Err("Cannot delete the default Ollama provider".into());
     }
     
     settings.providers.retain(|p| p.id != id);
+    secrets::delete(&secrets::provider_account(&id));
     
     // Reset active provider if deleted
     if settings.active_provider_id == Some(id.clone()) {
         settings.active_provider_id = Some("ollama-default".to_string());
     }
     
-    settings_set(settings.clone()).await?;
+    save_settings(settings.clone())?;
     Ok(settings.providers)
 }
 
 #[tauri::command]
-pub async fn provider_set_active(id: String) -> Result<Settings, String> {
+pub async fn provider_set_active(id: String) -> Result<Settings, OllieError> {
     let mut settings = settings_get().await?;
     
     if !settings.providers.iter().any(|p| p.id == id) {
-        return Err(format!("Provider with ID '{}' not found", id));
+        return Err(OllieError::not_found(format!("Provider with ID '{}' not found", id)));
     }
     
     settings.active_provider_id = Some(id);
-    settings_set(settings).await
+    save_settings(settings)
 }
 
 #[tauri::command]
-pub async fn provider_list() -> Result<Vec<ProviderConfig>, String> {
+pub async fn provider_list() -> Result<Vec<ProviderConfig>, OllieError> {
     let settings = settings_get().await?;
     Ok(settings.providers)
 }
 
 #[tauri::command]
-pub async fn provider_get_active() -> Result<ProviderConfig, String> {
+pub async fn provider_get_active() -> Result<ProviderConfig, OllieError> {
     let settings = settings_get().await?;
     let active_id = settings.active_provider_id.unwrap_or_else(|| "ollama-default".to_string());
     
     settings.providers.into_iter()
         .find(|p| p.id == active_id)
-        .ok_or_else(|| "Active provider not found".to_string())
+        .ok_or_else(|| OllieError::not_found("Active provider not found"))
+}
+
+#[tauri::command]
+pub async fn server_profile_add(profile: ServerProfile) -> Result<Vec<ServerProfile>, OllieError> {
+    let mut settings = settings_get().await?;
+    if profile.name.trim().is_empty() {
+        return Err("Server profile name cannot be empty".into());
+    }
+    if settings.servers.iter().any(|p| p.name == profile.name) {
+        return Err(format!("Server profile '{}' already exists", profile.name).into());
+    }
+    let profile = ServerProfile { url: normalize_server_url(&profile.url)?, ..profile };
+
+    settings.servers.push(profile);
+    let settings = save_settings(settings)?;
+    Ok(settings.servers)
+}
+
+/// Remove a profile; removing the active one switches to the first that remains
+#[tauri::command]
+pub async fn server_profile_remove(name: String) -> Result<Vec<ServerProfile>, OllieError> {
+    let mut settings = settings_get().await?;
+    if !settings.servers.iter().any(|p| p.name == name) {
+        return Err(OllieError::not_found(format!("Server profile '{}' not found", name)));
+    }
+    if settings.servers.len() == 1 {
+        return Err("Cannot remove the only server profile".into());
+    }
+
+    settings.servers.retain(|p| p.name != name);
+    secrets::delete(&secrets::server_account(&name));
+    if settings.active_server.as_deref() == Some(name.as_str()) {
+        settings.active_server = settings.servers.first().map(|p| p.name.clone());
+    }
+    let settings = save_settings(settings)?;
+    Ok(settings.servers)
+}
+
+#[tauri::command]
+pub async fn server_profile_activate(name: String) -> Result<Settings, OllieError> {
+    let mut settings = settings_get().await?;
+    if !settings.servers.iter().any(|p| p.name == name) {
+        return Err(OllieError::not_found(format!("Server profile '{}' not found", name)));
+    }
+
+    settings.active_server = Some(name);
+    save_settings(settings)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub problem: String,
+    /// True when the doctor repaired the problem itself
+    pub fixed: bool,
+}
+
+/// Check that the config dir, database, settings file and log dir exist and are writable,
+/// creating missing directories. Returns an empty list when everything is healthy.
+#[tauri::command]
+pub async fn config_doctor(app: tauri::AppHandle) -> Result<Vec<ConfigIssue>, OllieError> {
+    let mut issues = Vec::new();
+    let dir = config_dir_path()?;
+    check_dir(&dir, &mut issues);
+
+    let settings = dir.join("settings.json");
+    if settings.exists() {
+        match fs::read_to_string(&settings) {
+            Ok(content) => {
+                if let Err(e) = serde_json::from_str::<Settings>(&content) {
+                    issues.push(issue(&settings, format!("Invalid settings JSON: {}", e), false));
+                }
+            }
+            Err(e) => issues.push(issue(&settings, format!("Not readable: {}", e), false)),
+        }
+        check_file_writable(&settings, &mut issues);
+    }
+
+    // Chats live in the SQLite database alongside settings
+    let db = dir.join("app.db");
+    if db.exists() {
+        check_file_writable(&db, &mut issues);
+    }
+
+    match app.path().app_log_dir() {
+        Ok(log_dir) => check_dir(&log_dir, &mut issues),
+        Err(e) => issues.push(ConfigIssue { path: "logs".to_string(), problem: format!("Cannot resolve log dir: {}", e), fixed: false }),
+    }
+
+    Ok(issues)
+}
+
+fn issue(path: &std::path::Path, problem: String, fixed: bool) -> ConfigIssue {
+    ConfigIssue { path: path.display().to_string(), problem, fixed }
+}
+
+fn check_dir(dir: &std::path::Path, issues: &mut Vec<ConfigIssue>) {
+    if !dir.exists() {
+        match fs::create_dir_all(dir) {
+            Ok(_) => issues.push(issue(dir, "Directory was missing".to_string(), true)),
+            Err(e) => {
+                issues.push(issue(dir, format!("Directory missing and could not be created: {}", e), false));
+                return;
+            }
+        }
+    }
+    let probe = dir.join(".ollie-write-test");
+    match fs::write(&probe, b"ok") {
+        Ok(_) => { let _ = fs::remove_file(&probe); }
+        Err(e) => issues.push(issue(dir, format!("Directory is not writable: {}", e), false)),
+    }
+}
+
+fn check_file_writable(path: &std::path::Path, issues: &mut Vec<ConfigIssue>) {
+    // Opening for append checks permissions without touching the contents
+    if let Err(e) = fs::OpenOptions::new().append(true).open(path) {
+        issues.push(issue(path, format!("File is not writable: {}", e), false));
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettingsWarning {
+    /// Dotted location in the file, e.g. `providers[1].base_url`
+    pub path: String,
+    pub problem: String,
+}
+
+fn warning(path: impl Into<String>, problem: impl Into<String>) -> SettingsWarning {
+    SettingsWarning { path: path.into(), problem: problem.into() }
+}
+
+/// Read-only check of a hand-edited settings.json: duplicate and unknown keys,
+/// out-of-range default params and malformed URLs. Returns an empty list when clean.
+#[tauri::command]
+pub async fn settings_validate() -> Result<Vec<SettingsWarning>, OllieError> {
+    let path = settings_read_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read settings: {}", e))?;
+
+    let mut warnings = Vec::new();
+    let raw: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => return Ok(vec![warning("", format!("Invalid JSON: {}", e))]),
+    };
+
+    // serde_json keeps the last of repeated keys, so find them in a separate pass
+    let mut duplicates = Vec::new();
+    let mut de = serde_json::Deserializer::from_str(&content);
+    if serde::de::DeserializeSeed::deserialize(KeyAudit { path: String::new(), duplicates: &mut duplicates }, &mut de).is_ok() {
+        warnings.extend(duplicates.into_iter().map(|p| warning(p, "Duplicate key; only the last value is used")));
+    }
+
+    let settings: Settings = match serde_json::from_value(raw.clone()) {
+        Ok(s) => s,
+        Err(e) => {
+            warnings.push(warning("", format!("Does not match the settings schema: {}", e)));
+            return Ok(warnings);
+        }
+    };
+
+    // Anything the typed struct drops on a round trip is a key serde silently ignored
+    let known = serde_json::to_value(&settings)?;
+    unknown_keys(&raw, &known, "", &mut warnings);
+
+    if let Some(params) = &settings.default_params {
+        if let Some(t) = params.temperature {
+            if !(0.0..=2.0).contains(&t) {
+                warnings.push(warning("default_params.temperature", format!("{} is outside 0.0-2.0", t)));
+            }
+        }
+        if let Some(k) = params.top_k {
+            if k < 1 {
+                warnings.push(warning("default_params.top_k", format!("{} must be at least 1", k)));
+            }
+        }
+        if let Some(p) = params.top_p {
+            if !(0.0..=1.0).contains(&p) {
+                warnings.push(warning("default_params.top_p", format!("{} is outside 0.0-1.0", p)));
+            }
+        }
+        if let Some(m) = params.max_tokens {
+            if m < 1 {
+                warnings.push(warning("default_params.max_tokens", format!("{} must be at least 1", m)));
+            }
+        }
+    }
+
+    check_url("server_url", &settings.server_url, &mut warnings);
+    if let Some(url) = &settings.fallback_server_url {
+        check_url("fallback_server_url", url, &mut warnings);
+    }
+    for (i, provider) in settings.providers.iter().enumerate() {
+        if let Some(url) = &provider.base_url {
+            check_url(&format!("providers[{}].base_url", i), url, &mut warnings);
+        }
+        if provider.api_key.as_deref().is_some_and(|k| !k.is_empty() && !secrets::is_reference(k)) {
+            warnings.push(warning(format!("providers[{}].api_key", i), "Stored in plain text; no keychain was available"));
+        }
+    }
+
+    if let Some(theme) = &settings.theme {
+        if !THEMES.contains(&theme.as_str()) {
+            warnings.push(warning("theme", format!("Unknown theme '{}'", theme)));
+        }
+    }
+
+    if let Some(Err(e)) = settings.default_keep_alive.as_ref().map(KeepAlive::validate) {
+        warnings.push(warning("default_keep_alive", e));
+    }
+
+    if let Some(n) = settings.show_concurrency {
+        if !(1..=MAX_SHOW_CONCURRENCY).contains(&n) {
+            warnings.push(warning("show_concurrency", format!("{} is outside 1-{}", n, MAX_SHOW_CONCURRENCY)));
+        }
+    }
+    for (model, patterns) in &settings.strip_patterns {
+        for pattern in patterns {
+            if let Err(e) = regex::Regex::new(pattern) {
+                warnings.push(warning(format!("strip_patterns.{}", model), format!("Invalid regex '{}': {}", pattern, e)));
+            }
+        }
+    }
+    if let Some(n) = settings.max_concurrent_pulls {
+        if !(1..=MAX_CONCURRENT_PULLS).contains(&n) {
+            warnings.push(warning("max_concurrent_pulls", format!("{} is outside 1-{}", n, MAX_CONCURRENT_PULLS)));
+        }
+    }
+
+    Ok(warnings)
+}
+
+// Walks both documents together, so a typo at any depth (`servers[0]`, `timeouts`, ...) is caught
+fn unknown_keys(raw: &serde_json::Value, known: &serde_json::Value, prefix: &str, warnings: &mut Vec<SettingsWarning>) {
+    match (raw, known) {
+        (serde_json::Value::Object(raw), serde_json::Value::Object(known)) => {
+            for (key, value) in raw {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                match known.get(key) {
+                    Some(known_value) => unknown_keys(value, known_value, &path, warnings),
+                    None => warnings.push(warning(path, "Unknown field; it is ignored")),
+                }
+            }
+        }
+        (serde_json::Value::Array(raw), serde_json::Value::Array(known)) => {
+            for (i, (r, k)) in raw.iter().zip(known).enumerate() {
+                unknown_keys(r, k, &format!("{}[{}]", prefix, i), warnings);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_url(path: &str, url: &str, warnings: &mut Vec<SettingsWarning>) {
+    match reqwest::Url::parse(url) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => {}
+        Ok(u) => warnings.push(warning(path, format!("Unsupported URL scheme '{}'", u.scheme()))),
+        Err(e) => warnings.push(warning(path, format!("Invalid URL '{}': {}", url, e))),
+    }
+}
+
+// Walks any JSON document recording object keys that appear more than once
+struct KeyAudit<'a> {
+    path: String,
+    duplicates: &'a mut Vec<String>,
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for KeyAudit<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for KeyAudit<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<(), E> { Ok(()) }
+    fn visit_i64<E>(self, _: i64) -> Result<(), E> { Ok(()) }
+    fn visit_u64<E>(self, _: u64) -> Result<(), E> { Ok(()) }
+    fn visit_f64<E>(self, _: f64) -> Result<(), E> { Ok(()) }
+    fn visit_str<E>(self, _: &str) -> Result<(), E> { Ok(()) }
+    fn visit_unit<E>(self) -> Result<(), E> { Ok(()) }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut i = 0;
+        while seq
+            .next_element_seed(KeyAudit { path: format!("{}[{}]", self.path, i), duplicates: &mut *self.duplicates })?
+            .is_some()
+        {
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut seen = std::collections::HashSet::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let path = if self.path.is_empty() { key.clone() } else { format!("{}.{}", self.path, key) };
+            if !seen.insert(key) {
+                self.duplicates.push(path.clone());
+            }
+            map.next_value_seed(KeyAudit { path, duplicates: &mut *self.duplicates })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_keys_are_found_at_any_depth() {
+        let raw = serde_json::json!({
+            "theme": "dark",
+            "them": "dark",
+            "servers": [{ "name": "home", "url": "http://a", "api_kye": "x" }],
+            "timeouts": { "list": 10, "pul": 60 },
+        });
+        let known = serde_json::json!({
+            "theme": "dark",
+            "servers": [{ "name": "home", "url": "http://a", "api_key": null }],
+            "timeouts": { "list": 10, "pull": null },
+        });
+        let mut warnings = Vec::new();
+        unknown_keys(&raw, &known, "", &mut warnings);
+        let mut paths: Vec<_> = warnings.into_iter().map(|w| w.path).collect();
+        paths.sort();
+        assert_eq!(paths, ["servers[0].api_kye", "them", "timeouts.pul"]);
+    }
+
+    fn params(temperature: f64, top_k: i32, top_p: f64, max_tokens: i32) -> DefaultParams {
+        DefaultParams { temperature: Some(temperature), top_k: Some(top_k), top_p: Some(top_p), max_tokens: Some(max_tokens) }
+    }
+
+    #[test]
+    fn merge_prefers_every_field_the_overlay_sets() {
+        let merged = params(0.7, 40, 0.9, 512).merge(&params(0.2, 10, 0.5, 64));
+        assert_eq!(merged.temperature, Some(0.2));
+        assert_eq!(merged.top_k, Some(10));
+        assert_eq!(merged.top_p, Some(0.5));
+        assert_eq!(merged.max_tokens, Some(64));
+    }
+
+    #[test]
+    fn merge_falls_through_to_base_for_unset_fields() {
+        let merged = params(0.7, 40, 0.9, 512).merge(&DefaultParams::default());
+        assert_eq!(merged.temperature, Some(0.7));
+        assert_eq!(merged.top_k, Some(40));
+        assert_eq!(merged.top_p, Some(0.9));
+        assert_eq!(merged.max_tokens, Some(512));
+    }
+
+    #[test]
+    fn merge_mixes_fields_independently() {
+        let overlay = DefaultParams { temperature: Some(0.1), top_p: Some(0.3), ..Default::default() };
+        let base = DefaultParams { top_k: Some(20), top_p: Some(0.8), ..Default::default() };
+        let merged = base.merge(&overlay);
+        assert_eq!(merged.temperature, Some(0.1));
+        assert_eq!(merged.top_k, Some(20));
+        assert_eq!(merged.top_p, Some(0.3));
+        assert_eq!(merged.max_tokens, None);
+    }
 }
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::commands::db::{model_last_used, restore_chat_meta, search_chats, TaggedChat};
+use crate::commands::secrets;
+use crate::commands::settings::{normalize_settings, save_settings, stored_settings, Settings};
+use crate::commands::sys::ensure_writable;
+use crate::commands::error::OllieError;
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything needed to reproduce a user's setup, minus conversation bodies
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub version: u32,
+    pub created_at: i64,
+    /// Includes providers, default model/params and theme. API keys appear only as keychain
+    /// references; keys kept in plain text are left out.
+    pub settings: Settings,
+    /// Last use per model in epoch millis; derived from chats, so informational on restore
+    #[serde(default)]
+    pub recent_models: HashMap<String, i64>,
+    /// Chat metadata and tags, without messages
+    #[serde(default)]
+    pub chats: Vec<TaggedChat>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreSummary {
+    pub settings_restored: bool,
+    pub chats_restored: usize,
+}
+
+#[tauri::command]
+pub async fn state_snapshot() -> Result<StateSnapshot, OllieError> {
+    let mut settings = stored_settings().await?;
+    let keys = settings.providers.iter_mut().map(|p| &mut p.api_key).chain(settings.servers.iter_mut().map(|s| &mut s.api_key));
+    for api_key in keys {
+        if !api_key.as_deref().is_some_and(secrets::is_reference) {
+            *api_key = None;
+        }
+    }
+    Ok(StateSnapshot {
+        version: SNAPSHOT_VERSION,
+        created_at: chrono::Utc::now().timestamp_millis(),
+        settings,
+        recent_models: model_last_used().await?,
+        // A negative LIMIT means no limit in SQLite
+        chats: search_chats(None, None, -1).await?,
+    })
+}
+
+/// Reapply a snapshot: settings are replaced, chat metadata is upserted by id
+#[tauri::command]
+pub async fn state_restore(snapshot: StateSnapshot) -> Result<RestoreSummary, OllieError> {
+    ensure_writable("state_restore")?;
+    if snapshot.version > SNAPSHOT_VERSION {
+        return Err(format!(
+            "Snapshot version {} is newer than this app supports ({})",
+            snapshot.version, SNAPSHOT_VERSION
+        ).into());
+    }
+    let mut settings = snapshot.settings;
+    normalize_settings(&mut settings)?;
+    // Snapshots leave out keys that weren't in the keychain; keep whatever is stored for those
+    let stored = stored_settings().await?;
+    for provider in settings.providers.iter_mut().filter(|p| p.api_key.is_none()) {
+        provider.api_key = stored.providers.iter().find(|p| p.id == provider.id).and_then(|p| p.api_key.clone());
+    }
+    for server in settings.servers.iter_mut().filter(|s| s.api_key.is_none()) {
+        server.api_key = stored.servers.iter().find(|s| s.name == server.name).and_then(|s| s.api_key.clone());
+    }
+    save_settings(settings)?;
+    let chats_restored = restore_chat_meta(&snapshot.chats).await?;
+    Ok(RestoreSummary { settings_restored: true, chats_restored })
+}
@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::process::Command;
-use crate::commands::settings::get_ollama_url;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::commands::settings::{get_fallback_url, get_ollama_url, settings_get};
+use crate::commands::error::OllieError;
+use crate::commands::models::HttpClient;
+use crate::commands::events;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HealthStatus {
@@ -28,41 +35,335 @@ pub struct ServiceActionResult {
 }
 
 #[tauri::command]
-pub async fn server_health(url: Option<String>) -> Result<HealthStatus, String> {
+pub async fn server_health(url: Option<String>, http: State<'_, HttpClient>) -> Result<HealthStatus, OllieError> {
+    Ok(check_health(&http.client(), url).await)
+}
+
+async fn check_health(client: &reqwest::Client, url: Option<String>) -> HealthStatus {
     let server_url = url.unwrap_or_else(get_ollama_url);
     let health_url = format!("{}/api/tags", server_url);
     
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| e.to_string())?;
-    
-    match client.get(&health_url).send().await {
+    match client.get(&health_url).timeout(Duration::from_secs(5)).send().await {
         Ok(response) => {
             if response.status().is_success() {
-                Ok(HealthStatus {
+                HealthStatus {
                     connected: true,
                     url: server_url,
                     error: None,
-                })
+                }
             } else {
-                Ok(HealthStatus {
+                HealthStatus {
                     connected: false,
                     url: server_url,
                     error: Some(format!("Server returned status: {}", response.status())),
-                })
+                }
             }
         }
-        Err(e) => Ok(HealthStatus {
+        Err(e) => HealthStatus {
             connected: false,
             url: server_url,
             error: Some(e.to_string()),
+        },
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerFailure {
+    /// Nothing listening on the port: Ollama isn't running
+    ConnectionRefused,
+    /// Something accepted the connection but didn't answer in time
+    TimedOut,
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub reachable: bool,
+    pub version: Option<String>,
+    pub error: Option<String>,
+    pub failure: Option<ServerFailure>,
+}
+
+fn classify_request_error(e: &reqwest::Error) -> ServerFailure {
+    if e.is_timeout() {
+        return ServerFailure::TimedOut;
+    }
+    let mut source = std::error::Error::source(e);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            match io.kind() {
+                std::io::ErrorKind::ConnectionRefused => return ServerFailure::ConnectionRefused,
+                std::io::ErrorKind::TimedOut => return ServerFailure::TimedOut,
+                _ => {}
+            }
+        }
+        source = err.source();
+    }
+    ServerFailure::Other
+}
+
+/// Quick reachability check against `/api/version`, with failures classified so the UI can
+/// tell "Ollama not running" from "server slow"
+#[tauri::command]
+pub async fn server_status(server_url: Option<String>, http: State<'_, HttpClient>) -> Result<ServerStatus, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let client = http.client();
+
+    let failed = |failure: ServerFailure, error: String| ServerStatus {
+        reachable: false,
+        version: None,
+        error: Some(error),
+        failure: Some(failure),
+    };
+    let resp = match client.get(format!("{}/api/version", url.trim_end_matches('/'))).timeout(Duration::from_secs(3)).send().await {
+        Ok(r) => r,
+        Err(e) => return Ok(failed(classify_request_error(&e), e.to_string())),
+    };
+    if !resp.status().is_success() {
+        return Ok(failed(ServerFailure::Other, format!("Server returned status: {}", resp.status())));
+    }
+    match resp.json::<serde_json::Value>().await {
+        Ok(body) => Ok(ServerStatus {
+            reachable: true,
+            version: body.get("version").and_then(|v| v.as_str()).map(String::from),
+            error: None,
+            failure: None,
         }),
+        Err(e) => Ok(failed(classify_request_error(&e), e.to_string())),
     }
 }
 
+pub struct FailoverResponse {
+    pub response: reqwest::Response,
+    /// Base URL of the server that answered
+    pub served_by: String,
+    /// Why the primary was skipped, when the fallback answered instead
+    pub primary_error: Option<String>,
+}
+
+impl FailoverResponse {
+    /// Emit `server:failover` when the fallback served the request
+    pub fn report(&self, app: &AppHandle, primary: &str) {
+        if let Some(error) = &self.primary_error {
+            let _ = app.emit(events::SERVER_FAILOVER, &serde_json::json!({
+                "primary": primary,
+                "fallback": self.served_by,
+                "error": error
+            }));
+        }
+    }
+}
+
+/// Send the request `build` makes for a base URL. Unless the caller pinned `server_url`,
+/// a connection failure on the configured server is retried once on `fallback_server_url`.
+pub async fn send_with_failover(
+    server_url: Option<String>,
+    build: impl Fn(&str) -> reqwest::RequestBuilder,
+) -> Result<FailoverResponse, OllieError> {
+    let pinned = server_url.is_some();
+    let primary = server_url.unwrap_or_else(get_ollama_url);
+    let error = match build(&primary).send().await {
+        Ok(response) => return Ok(FailoverResponse { response, served_by: primary, primary_error: None }),
+        Err(e) => e,
+    };
+    let fallback = get_fallback_url().filter(|f| !pinned && error.is_connect() && f.trim_end_matches('/') != primary.trim_end_matches('/'));
+    let Some(fallback) = fallback else {
+        return Err(error.into());
+    };
+    eprintln!("{} unreachable ({}); failing over to {}", primary, error, fallback);
+    // Both down is still a network failure, so keep the fallback's classification
+    let response = build(&fallback).send().await.map_err(|e| {
+        let message = format!("{} (fallback {} also failed: {})", error, fallback, e);
+        match OllieError::from(e) {
+            OllieError::NetworkUnreachable { .. } => OllieError::NetworkUnreachable { message },
+            OllieError::Timeout { .. } => OllieError::Timeout { message },
+            _ => OllieError::Other { message },
+        }
+    })?;
+    Ok(FailoverResponse { response, served_by: fallback, primary_error: Some(error.to_string()) })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerCapacity {
+    pub connected: bool,
+    pub url: String,
+    pub model: Option<String>,
+    /// The model loaded into memory, so the server can actually serve it
+    pub can_load: bool,
+    pub load_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+const CAPACITY_LOAD_TIMEOUT_SECS: u64 = 120;
+
+/// Health check that also loads `model` (default: the configured default model) with a short
+/// keep_alive, so a server without room for it doesn't report as usable
+#[tauri::command]
+pub async fn server_capacity(model: Option<String>, url: Option<String>, http: State<'_, HttpClient>) -> Result<ServerCapacity, OllieError> {
+    let client = http.client();
+    let health = check_health(&client, url).await;
+    let model = match model {
+        Some(m) => Some(m),
+        None => settings_get().await.ok().and_then(|s| s.default_model),
+    };
+    let mut capacity = ServerCapacity {
+        connected: health.connected,
+        url: health.url,
+        model: model.clone(),
+        can_load: false,
+        load_ms: None,
+        error: health.error,
+    };
+    if !capacity.connected {
+        return Ok(capacity);
+    }
+    let Some(model) = model else {
+        capacity.error = Some("No model given and no default model configured".to_string());
+        return Ok(capacity);
+    };
+
+    // A generate request without a prompt only loads the model
+    let started = std::time::Instant::now();
+    let response = client
+        .post(format!("{}/api/generate", capacity.url))
+        .timeout(Duration::from_secs(CAPACITY_LOAD_TIMEOUT_SECS))
+        .json(&serde_json::json!({ "model": model, "keep_alive": "30s", "stream": false }))
+        .send()
+        .await;
+    match response {
+        Ok(r) if r.status().is_success() => {
+            let body: serde_json::Value = r.json().await.unwrap_or_default();
+            capacity.can_load = true;
+            capacity.load_ms = body
+                .get("load_duration")
+                .and_then(|d| d.as_u64())
+                .map(|ns| ns / 1_000_000)
+                .or_else(|| Some(started.elapsed().as_millis() as u64));
+        }
+        Ok(r) => {
+            let status = r.status();
+            let body = r.text().await.unwrap_or_default();
+            let reason = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+            capacity.error = Some(match reason {
+                Some(reason) => format!("Failed to load model: {}", reason),
+                None => format!("Failed to load model: HTTP {}", status),
+            });
+        }
+        Err(e) if e.is_timeout() => {
+            capacity.error = Some(format!("Model did not load within {}s", CAPACITY_LOAD_TIMEOUT_SECS));
+        }
+        Err(e) => capacity.error = Some(e.to_string()),
+    }
+    Ok(capacity)
+}
+
+// Demo/read-only mode: destructive commands refuse to run while set
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Fail with a read-only error when demo mode is on; call before any destructive action
+pub fn ensure_writable(action: &str) -> Result<(), OllieError> {
+    if READ_ONLY.load(Ordering::Relaxed) {
+        Err(OllieError::ReadOnly { message: format!("{} is disabled while read-only mode is on", action) })
+    } else {
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn read_only_set(enabled: bool) -> bool {
+    READ_ONLY.store(enabled, Ordering::Relaxed);
+    enabled
+}
+
+#[tauri::command]
+pub fn read_only_get() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Last known reachability of the active server, maintained by the connection monitor
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionState {
+    pub connected: bool,
+    pub url: String,
+    pub error: Option<String>,
+    /// Epoch millis of the latest check
+    pub last_checked: u64,
+    /// Epoch millis of the latest flip between connected and disconnected
+    pub last_changed: Option<u64>,
+}
+
+pub type SharedConnectionState = Arc<Mutex<ConnectionState>>;
+
+const CONNECTION_POLL_SECS: u64 = 5;
+
+lazy_static::lazy_static! {
+    // Whether the main window is focused; background pollers idle while it is false
+    static ref WINDOW_ACTIVE: tokio::sync::watch::Sender<bool> = tokio::sync::watch::Sender::new(true);
+}
+
+/// Record a focus change from the window event hook
+pub fn set_window_active(active: bool) {
+    WINDOW_ACTIVE.send_replace(active);
+}
+
+/// Block while the window is unfocused or minimized. Returns true when it had to wait,
+/// so the caller can refresh immediately instead of waiting out its interval.
+pub async fn wait_for_window_active() -> bool {
+    let mut rx = WINDOW_ACTIVE.subscribe();
+    if *rx.borrow() {
+        return false;
+    }
+    let _ = rx.wait_for(|active| *active).await;
+    true
+}
+
+/// Poll `server_health` in the background and emit `server:connected` /
+/// `server:disconnected` only when reachability flips (including the first check)
+pub fn spawn_connection_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(CONNECTION_POLL_SECS));
+        let mut previous: Option<bool> = None;
+        loop {
+            interval.tick().await;
+            if wait_for_window_active().await {
+                interval.reset();
+            }
+            let health = check_health(&app.state::<HttpClient>().client(), None).await;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+            let changed = previous != Some(health.connected);
+            previous = Some(health.connected);
+
+            let snapshot = {
+                let state = app.state::<SharedConnectionState>();
+                let mut current = state.lock().unwrap();
+                current.connected = health.connected;
+                current.url = health.url;
+                current.error = health.error;
+                current.last_checked = now;
+                if changed {
+                    current.last_changed = Some(now);
+                }
+                current.clone()
+            };
+
+            if changed {
+                let event = if snapshot.connected { events::SERVER_CONNECTED } else { events::SERVER_DISCONNECTED };
+                let _ = app.emit(event, &snapshot);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn connection_state(state: State<'_, SharedConnectionState>) -> ConnectionState {
+    state.lock().unwrap().clone()
+}
+
 #[tauri::command]
-pub async fn detect_ollama() -> Result<OllamaDetectionResult, String> {
+pub async fn detect_ollama(http: State<'_, HttpClient>) -> Result<OllamaDetectionResult, OllieError> {
     let mut result = OllamaDetectionResult {
         installed: false,
         version: None,
@@ -135,7 +436,7 @@ pub async fn detect_ollama() -> Result<OllamaDetectionResult, String> {
         }
         
         // Check if service is running
-        result.service_running = is_ollama_service_running().await;
+        result.service_running = is_ollama_service_running(&http.client()).await;
         
         // Check if service is enabled (systemd)
         if let Ok(output) = Command::new("systemctl").args(["is-enabled", "ollama"]).output() {
@@ -152,13 +453,13 @@ pub async fn detect_ollama() -> Result<OllamaDetectionResult, String> {
 }
 
 #[tauri::command]
-pub async fn start_ollama_service() -> Result<ServiceActionResult, String> {
+pub async fn start_ollama_service(http: State<'_, HttpClient>) -> Result<ServiceActionResult, OllieError> {
     // Try different methods to start Ollama
     
     // Method 1: Try systemd service
     if let Ok(output) = Command::new("systemctl").args(["start", "ollama"]).output() {
         if output.status.success() {
-            let running = is_ollama_service_running().await;
+            let running = is_ollama_service_running(&http.client()).await;
             return Ok(ServiceActionResult {
                 success: true,
                 message: "Ollama service started via systemd".to_string(),
@@ -174,7 +475,7 @@ pub async fn start_ollama_service() -> Result<ServiceActionResult, String> {
     {
         // Give it a moment to start
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-        let running = is_ollama_service_running().await;
+        let running = is_ollama_service_running(&http.client()).await;
         
         if running {
             return Ok(ServiceActionResult {
@@ -193,7 +494,7 @@ pub async fn start_ollama_service() -> Result<ServiceActionResult, String> {
 }
 
 #[tauri::command]
-pub async fn stop_ollama_service() -> Result<ServiceActionResult, String> {
+pub async fn stop_ollama_service(http: State<'_, HttpClient>) -> Result<ServiceActionResult, OllieError> {
     // Method 1: Try systemd service
     if let Ok(output) = Command::new("systemctl").args(["stop", "ollama"]).output() {
         if output.status.success() {
@@ -219,22 +520,16 @@ pub async fn stop_ollama_service() -> Result<ServiceActionResult, String> {
     Ok(ServiceActionResult {
         success: false,
         message: "Could not stop Ollama service. It may not be running or may require manual intervention".to_string(),
-        service_running: is_ollama_service_running().await,
+        service_running: is_ollama_service_running(&http.client()).await,
     })
 }
 
 // Helper functions
-async fn is_ollama_service_running() -> bool {
+async fn is_ollama_service_running(client: &reqwest::Client) -> bool {
     // Check if we can connect to Ollama API (use configured URL)
     let base_url = get_ollama_url();
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(3))
-        .build();
-        
-    if let Ok(client) = client {
-        if let Ok(response) = client.get(format!("{}/api/tags", base_url)).send().await {
-            return response.status().is_success();
-        }
+    if let Ok(response) = client.get(format!("{}/api/tags", base_url)).timeout(Duration::from_secs(3)).send().await {
+        return response.status().is_success();
     }
     
     // Fallback: check process
@@ -245,6 +540,34 @@ async fn is_ollama_service_running() -> bool {
     false
 }
 
+/// Best guess at where the local Ollama server keeps its models
+/// (`OLLAMA_MODELS`, the per-user dir, or the Linux service account's dir)
+pub fn ollama_models_dir() -> std::path::PathBuf {
+    if let Ok(dir) = std::env::var("OLLAMA_MODELS") {
+        if !dir.is_empty() {
+            return std::path::PathBuf::from(dir);
+        }
+    }
+    let user_dir = std::path::PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".ollama").join("models");
+    let service_dir = std::path::PathBuf::from("/usr/share/ollama/.ollama/models");
+    if !user_dir.exists() && service_dir.exists() {
+        service_dir
+    } else {
+        user_dir
+    }
+}
+
+/// Free bytes on the disk holding `path`, picking the most specific mount point
+pub fn available_space(path: &std::path::Path) -> Option<u64> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|d| path.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(|d| d.available_space())
+}
+
 fn get_install_suggestions() -> Vec<String> {
     vec![
         // Official installation script
@@ -260,4 +583,258 @@ fn get_install_suggestions() -> Vec<String> {
         "# Via package manager (if available):".to_string(),
         "# Check your distribution's package manager for 'ollama'".to_string(),
     ]
-}
\ No newline at end of file
+}
+/// Open an Ollie-managed folder (config/data, logs, or the Ollama models dir) in the
+/// platform file manager. Files are shown by opening their containing folder.
+#[tauri::command]
+pub async fn reveal_in_file_manager(app: AppHandle, path: String) -> Result<(), OllieError> {
+    let target = std::fs::canonicalize(&path).map_err(|e| format!("Cannot open {}: {}", path, e))?;
+    let dir = if target.is_dir() { target } else { target.parent().map(|p| p.to_path_buf()).unwrap_or(target) };
+
+    let mut allowed = vec![ollama_models_dir()];
+    allowed.extend(crate::commands::settings::config_dir_path().ok());
+    allowed.extend(app.path().app_log_dir().ok());
+    let permitted = allowed
+        .iter()
+        .filter_map(|root| std::fs::canonicalize(root).ok())
+        .any(|root| dir.starts_with(root));
+    if !permitted {
+        return Err(format!("{} is not a folder managed by Ollie", dir.display()).into());
+    }
+
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    Command::new(opener)
+        .arg(&dir)
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", opener, e))?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PortStatus {
+    pub host: String,
+    pub port: u16,
+    /// Whether something accepts TCP connections on the port
+    pub open: bool,
+    /// Whether the listener answers like Ollama; None when the port is closed or not local
+    pub is_ollama: Option<bool>,
+    pub message: String,
+}
+
+/// Diagnose a localhost server URL: is the port open, and is it Ollama that answers?
+#[tauri::command]
+pub async fn port_check(server_url: Option<String>, http: State<'_, HttpClient>) -> Result<PortStatus, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let parsed = reqwest::Url::parse(&url).map_err(|e| format!("Invalid server URL '{}': {}", url, e))?;
+    let host = parsed.host_str().unwrap_or_default().trim_matches(['[', ']']).to_string();
+    let port = parsed.port_or_known_default().ok_or_else(|| format!("No port in '{}'", url))?;
+
+    if !is_local_host(&host) {
+        return Ok(PortStatus {
+            host,
+            port,
+            open: false,
+            is_ollama: None,
+            message: "Port checks only apply to servers on this machine".to_string(),
+        });
+    }
+
+    let connect_host = if host == "0.0.0.0" { "127.0.0.1" } else { host.as_str() };
+    let connect = tokio::net::TcpStream::connect((connect_host, port));
+    let open = matches!(tokio::time::timeout(Duration::from_secs(2), connect).await, Ok(Ok(_)));
+    if !open {
+        return Ok(PortStatus {
+            host,
+            port,
+            open,
+            is_ollama: None,
+            message: format!("Nothing is listening on port {}. Is Ollama running?", port),
+        });
+    }
+
+    // Ollama answers `GET /` with "Ollama is running" and `/api/version` with a version
+    let client = http.client();
+    let base = url.trim_end_matches('/');
+    let root_ok = match client.get(base).timeout(Duration::from_secs(3)).send().await {
+        Ok(resp) => resp.text().await.is_ok_and(|body| body.contains("Ollama is running")),
+        Err(_) => false,
+    };
+    let is_ollama = root_ok || match client.get(format!("{}/api/version", base)).timeout(Duration::from_secs(3)).send().await {
+        Ok(resp) => resp.json::<serde_json::Value>().await.is_ok_and(|v| v.get("version").is_some()),
+        Err(_) => false,
+    };
+
+    let message = if is_ollama {
+        format!("Ollama is listening on port {}", port)
+    } else {
+        format!(
+            "Another service is using port {}; it does not respond like Ollama. Stop it or point Ollie at Ollama's actual address (OLLAMA_HOST).",
+            port
+        )
+    };
+    Ok(PortStatus { host, port, open, is_ollama: Some(is_ollama), message })
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LatencyReport {
+    pub samples: u32,
+    pub failures: u32,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+}
+
+const MAX_LATENCY_SAMPLES: u32 = 1000;
+
+/// Time `samples` sequential `/api/version` round trips; statistics cover successful ones only
+#[tauri::command]
+pub async fn latency_histogram(server_url: Option<String>, samples: u32, http: State<'_, HttpClient>) -> Result<LatencyReport, OllieError> {
+    if samples == 0 || samples > MAX_LATENCY_SAMPLES {
+        return Err(format!("samples must be between 1 and {}", MAX_LATENCY_SAMPLES).into());
+    }
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let endpoint = format!("{}/api/version", url.trim_end_matches('/'));
+    let client = http.client();
+
+    let mut times = Vec::with_capacity(samples as usize);
+    let mut failures = 0;
+    for _ in 0..samples {
+        let started = std::time::Instant::now();
+        let ok = match client.get(&endpoint).timeout(Duration::from_secs(5)).send().await {
+            // Include reading the body so the sample is a full round trip
+            Ok(resp) if resp.status().is_success() => resp.bytes().await.is_ok(),
+            _ => false,
+        };
+        if ok {
+            times.push(started.elapsed().as_secs_f64() * 1000.0);
+        } else {
+            failures += 1;
+        }
+    }
+    if times.is_empty() {
+        return Err(format!("All {} requests to {} failed", samples, endpoint).into());
+    }
+
+    times.sort_by(|a, b| a.total_cmp(b));
+    // Nearest-rank percentile
+    let percentile = |p: f64| times[((p / 100.0 * times.len() as f64).ceil() as usize).clamp(1, times.len()) - 1];
+    Ok(LatencyReport {
+        samples,
+        failures,
+        min_ms: times[0],
+        max_ms: times[times.len() - 1],
+        mean_ms: times.iter().sum::<f64>() / times.len() as f64,
+        p50_ms: percentile(50.0),
+        p95_ms: percentile(95.0),
+    })
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Locality {
+    Local,
+    Remote,
+}
+
+/// Loopback names and addresses, plus the bind-all address that means this machine
+pub fn is_local_host(host: &str) -> bool {
+    let host = host.trim_matches(['[', ']']).to_lowercase();
+    if host == "localhost" || host.ends_with(".localhost") || host == "0.0.0.0" {
+        return true;
+    }
+    host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Whether the server runs on this machine; blob checks, disk checks and starting the
+/// service only make sense when it does
+#[tauri::command]
+pub fn server_locality(server_url: Option<String>) -> Result<Locality, OllieError> {
+    let url = server_url.unwrap_or_else(get_ollama_url);
+    let url = url.trim();
+    // A unix socket is local by definition
+    if url.starts_with('/') || url.starts_with("unix:") || url.starts_with("http+unix:") {
+        return Ok(Locality::Local);
+    }
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid server URL '{}': {}", url, e))?;
+    let host = parsed.host_str().unwrap_or_default();
+    Ok(if is_local_host(host) { Locality::Local } else { Locality::Remote })
+}
+
+/// Ollama's own configuration as seen in this process's environment
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaEnv {
+    /// `OLLAMA_HOST` exactly as set
+    pub host: Option<String>,
+    /// `OLLAMA_HOST` as a URL a client can connect to
+    pub server_url: Option<String>,
+    pub models_dir: Option<String>,
+    pub keep_alive: Option<String>,
+    pub origins: Option<String>,
+    pub num_parallel: Option<String>,
+    pub max_loaded_models: Option<String>,
+    pub max_queue: Option<String>,
+    pub flash_attention: Option<String>,
+    pub context_length: Option<String>,
+    pub debug: Option<String>,
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Read the OLLAMA_* variables so first-run settings can match an existing Ollama setup
+#[tauri::command]
+pub fn detect_ollama_env() -> OllamaEnv {
+    let host = env_var("OLLAMA_HOST");
+    OllamaEnv {
+        server_url: host.as_deref().and_then(normalize_ollama_host),
+        host,
+        models_dir: env_var("OLLAMA_MODELS"),
+        keep_alive: env_var("OLLAMA_KEEP_ALIVE"),
+        origins: env_var("OLLAMA_ORIGINS"),
+        num_parallel: env_var("OLLAMA_NUM_PARALLEL"),
+        max_loaded_models: env_var("OLLAMA_MAX_LOADED_MODELS"),
+        max_queue: env_var("OLLAMA_MAX_QUEUE"),
+        flash_attention: env_var("OLLAMA_FLASH_ATTENTION"),
+        context_length: env_var("OLLAMA_CONTEXT_LENGTH"),
+        debug: env_var("OLLAMA_DEBUG"),
+    }
+}
+
+/// Server URL from `OLLAMA_HOST`, if set
+pub fn ollama_host_url() -> Option<String> {
+    env_var("OLLAMA_HOST").as_deref().and_then(normalize_ollama_host)
+}
+
+// OLLAMA_HOST accepts `host`, `host:port`, `:port` or a full URL; Ollama fills in the rest
+fn normalize_ollama_host(raw: &str) -> Option<String> {
+    let raw = raw.trim().trim_end_matches('/');
+    if raw.is_empty() {
+        return None;
+    }
+    let (scheme, rest) = match raw.split_once("://") {
+        Some((scheme, rest)) => (scheme, rest),
+        None => ("http", raw),
+    };
+    let default_port = if scheme == "https" { 443 } else { 11434 };
+    // Split a trailing :port, leaving bracketed IPv6 hosts intact
+    let (host, port) = match rest.rsplit_once(':') {
+        Some((h, p)) if !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()) => (h, p.parse::<u16>().ok()?),
+        _ => (rest, default_port),
+    };
+    // A bind-all address is where the server listens, not where a client should connect
+    let host = match host {
+        "" | "0.0.0.0" => "127.0.0.1",
+        "[::]" => "[::1]",
+        h => h,
+    };
+    Some(format!("{}://{}:{}", scheme, host, port))
+}
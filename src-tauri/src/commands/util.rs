@@ -0,0 +1,84 @@
+const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+/// Byte count the way `ollama list` prints it: decimal units, one decimal place ("4.1 GB").
+/// Negative sizes read as 0.
+pub fn format_bytes(bytes: i64) -> String {
+    let bytes = bytes.max(0);
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    // Move up a unit when the rounded figure would read 1000.0
+    while value >= 999.95 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Ollama's `parameter_size` ("7B", "70.6B", "110M") as a plain count. Anything else,
+/// including a number without a suffix, gives None rather than a guess.
+pub fn parse_parameter_count(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let suffix = size.chars().last()?;
+    let scale = match suffix.to_ascii_uppercase() {
+        'K' => 1e3,
+        'M' => 1e6,
+        'B' => 1e9,
+        'T' => 1e12,
+        _ => return None,
+    };
+    let number: f64 = size[..size.len() - suffix.len_utf8()].trim().parse().ok()?;
+    (number.is_finite() && number >= 0.0).then(|| (number * scale).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_bytes_stays_in_bytes_below_1000() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(-5), "0 B");
+    }
+
+    #[test]
+    fn format_bytes_switches_unit_at_each_boundary() {
+        assert_eq!(format_bytes(1_000), "1.0 KB");
+        assert_eq!(format_bytes(1_000_000), "1.0 MB");
+        assert_eq!(format_bytes(1_000_000_000), "1.0 GB");
+        assert_eq!(format_bytes(1_000_000_000_000), "1.0 TB");
+        assert_eq!(format_bytes(4_100_000_000), "4.1 GB");
+    }
+
+    #[test]
+    fn format_bytes_rolls_over_instead_of_printing_1000() {
+        assert_eq!(format_bytes(999_949), "999.9 KB");
+        assert_eq!(format_bytes(999_950), "1.0 MB");
+        assert_eq!(format_bytes(999_950_000_000), "1.0 TB");
+    }
+
+    #[test]
+    fn format_bytes_tops_out_at_terabytes() {
+        assert_eq!(format_bytes(5_000_000_000_000_000), "5000.0 TB");
+    }
+
+    #[test]
+    fn parse_parameter_count_reads_billions_and_millions() {
+        assert_eq!(parse_parameter_count("7B"), Some(7_000_000_000));
+        assert_eq!(parse_parameter_count("70.6B"), Some(70_600_000_000));
+        assert_eq!(parse_parameter_count("110M"), Some(110_000_000));
+    }
+
+    #[test]
+    fn parse_parameter_count_rejects_garbage() {
+        assert_eq!(parse_parameter_count(""), None);
+        assert_eq!(parse_parameter_count("garbage"), None);
+        assert_eq!(parse_parameter_count("B"), None);
+        assert_eq!(parse_parameter_count("7"), None);
+        assert_eq!(parse_parameter_count("-1B"), None);
+        assert_eq!(parse_parameter_count("7Q"), None);
+    }
+}
@@ -1,23 +1,53 @@
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
-use std::path::PathBuf;
+use sqlx::{SqliteConnection, SqlitePool, sqlite::SqlitePoolOptions};
+use std::path::{Path, PathBuf};
 use std::fs;
 use tokio::sync::Mutex;
+use crate::commands::settings::config_dir_path;
 
 lazy_static::lazy_static! {
 	static ref POOL: Mutex<Option<SqlitePool>> = Mutex::new(None);
 }
 
+// Same directory as settings.json, so Ollie's data stays in one place
 fn data_dir() -> Result<PathBuf, String> {
-	let home = std::env::var("HOME").map_err(|e| format!("Cannot read HOME env var: {}", e))?;
-	let dir = PathBuf::from(home).join(".config").join("ollie");
+	let dir = config_dir_path()?;
 	if !dir.exists() {
 		fs::create_dir_all(&dir).map_err(|e| format!("Failed to create data dir {}: {}", dir.display(), e))?;
 	}
 	Ok(dir)
 }
 
-// DB file path: ~/.config/ollama-gui/app.db
-fn db_path() -> Result<PathBuf, String> { Ok(data_dir()?.join("app.db")) }
+/// Where app.db lived before the platform config dir was used; only set when it differs
+fn legacy_db_path() -> Option<PathBuf> {
+	let home = std::env::var("HOME").ok()?;
+	let legacy = PathBuf::from(home).join(".config").join("ollie").join("app.db");
+	let current = config_dir_path().ok()?.join("app.db");
+	(legacy != current).then_some(legacy)
+}
+
+/// DB file path: app.db in the config dir, moved over from the legacy location on first use
+pub fn db_path() -> Result<PathBuf, String> {
+	let path = data_dir()?.join("app.db");
+	if !path.exists() {
+		if let Some(legacy) = legacy_db_path().filter(|p| p.exists()) {
+			move_db(&legacy, &path).map_err(|e| format!("Failed to move {} to {}: {}", legacy.display(), path.display(), e))?;
+			eprintln!("Moved database from {} to {}", legacy.display(), path.display());
+		}
+	}
+	Ok(path)
+}
+
+// Moves a database file along with its WAL/SHM siblings
+fn move_db(from: &Path, to: &Path) -> std::io::Result<()> {
+	fs::rename(from, to)?;
+	for suffix in ["-wal", "-shm"] {
+		let sibling = PathBuf::from(format!("{}{}", from.display(), suffix));
+		if sibling.exists() {
+			fs::rename(&sibling, format!("{}{}", to.display(), suffix))?;
+		}
+	}
+	Ok(())
+}
 
 pub async fn get_pool() -> Result<SqlitePool, String> {
 	let mut guard = POOL.lock().await;
@@ -29,13 +59,16 @@ pub async fn get_pool() -> Result<SqlitePool, String> {
 	if !path.exists() {
 		fs::File::create(&path).map_err(|e| format!("Failed to create db file: {}", e))?;
 	}
-	// Use proper SQLite URL and open mode (read/write/create)
-	let conn_str = format!("sqlite://{}?mode=rwc", path.to_string_lossy());
-	let pool = SqlitePoolOptions::new()
-		.max_connections(5)
-		.connect(&conn_str)
-		.await
-		.map_err(|e| format!("DB connect failed: {}", e))?;
+	let mut pool = open_pool(&path).await?;
+	// A crash mid-write can leave the file unreadable; keep a copy aside and start fresh
+	// instead of failing every chat command from here on
+	if !integrity_ok(&pool).await? {
+		pool.close().await;
+		let backup = backup_corrupt_db(&path)?;
+		eprintln!("Database {} failed integrity check, moved to {}", path.display(), backup.display());
+		fs::File::create(&path).map_err(|e| format!("Failed to create db file: {}", e))?;
+		pool = open_pool(&path).await?;
+	}
 
 	// Apply minimal schema (execute statements individually for SQLite)
 	// Enable WAL and foreign keys
@@ -72,18 +105,76 @@ pub async fn get_pool() -> Result<SqlitePool, String> {
 			FOREIGN KEY(chat_id) REFERENCES chats(id) ON DELETE CASCADE
 		)"#
 	).execute(&pool).await.map_err(|e| format!("DB migrate messages failed: {}", e))?;
+	sqlx::query(
+		r#"CREATE TABLE IF NOT EXISTS chat_tags (
+			chat_id TEXT NOT NULL,
+			tag TEXT NOT NULL,
+			PRIMARY KEY(chat_id, tag),
+			FOREIGN KEY(chat_id) REFERENCES chats(id) ON DELETE CASCADE
+		)"#
+	).execute(&pool).await.map_err(|e| format!("DB migrate chat_tags failed: {}", e))?;
 
 	*guard = Some(pool.clone());
 	Ok(pool)
 }
 
-pub async fn touch_chat_updated(pool: &SqlitePool, chat_id: &str) -> Result<(), String> {
+async fn open_pool(path: &Path) -> Result<SqlitePool, String> {
+	// Use proper SQLite URL and open mode (read/write/create)
+	let conn_str = format!("sqlite://{}?mode=rwc", path.to_string_lossy());
+	SqlitePoolOptions::new()
+		.max_connections(5)
+		.connect(&conn_str)
+		.await
+		.map_err(|e| format!("DB connect failed: {}", e))
+}
+
+// Only a verdict from SQLite counts as corruption; a busy or locked file (say, a second
+// instance holding it) is an error, not a reason to move the user's chats aside
+async fn integrity_ok(pool: &SqlitePool) -> Result<bool, String> {
+	match sqlx::query_scalar::<_, String>("PRAGMA quick_check").fetch_one(pool).await {
+		Ok(result) => Ok(result == "ok"),
+		Err(sqlx::Error::Database(e)) if e.code().and_then(|c| c.parse::<i32>().ok()).is_some_and(is_corruption_code) => Ok(false),
+		Err(e) => Err(format!("DB integrity check failed: {}", e)),
+	}
+}
+
+// SQLITE_CORRUPT and SQLITE_NOTADB, including their extended codes
+fn is_corruption_code(code: i32) -> bool {
+	matches!(code & 0xff, 11 | 26)
+}
+
+// Moves app.db (and its WAL/SHM siblings) to app.db.corrupt-<timestamp>
+fn backup_corrupt_db(path: &Path) -> Result<PathBuf, String> {
+	let stamp = chrono::Utc::now().timestamp();
+	let backup = PathBuf::from(format!("{}.corrupt-{}", path.display(), stamp));
+	move_db(path, &backup).map_err(|e| format!("Failed to back up corrupt db: {}", e))?;
+	Ok(backup)
+}
+
+pub async fn touch_chat_updated(conn: &mut SqliteConnection, chat_id: &str) -> Result<(), String> {
 	let now = chrono::Utc::now().timestamp_millis();
 	sqlx::query("UPDATE chats SET updated_at=? WHERE id=?")
 		.bind(now)
 		.bind(chat_id)
-		.execute(pool)
+		.execute(conn)
 		.await
 		.map_err(|e| format!("Failed to update chat: {}", e))?;
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_corrupt_and_notadb_codes_count_as_corruption() {
+		assert!(is_corruption_code(11));
+		assert!(is_corruption_code(26));
+		// SQLITE_CORRUPT_VTAB
+		assert!(is_corruption_code(267));
+		// SQLITE_BUSY, SQLITE_LOCKED, SQLITE_BUSY_SNAPSHOT
+		assert!(!is_corruption_code(5));
+		assert!(!is_corruption_code(6));
+		assert!(!is_corruption_code(517));
+	}
+}
@@ -11,18 +11,72 @@ pub fn run() {
     .plugin(tauri_plugin_shell::init())
     .invoke_handler(tauri::generate_handler![
       commands::sys::server_health,
+      commands::sys::server_status,
+      commands::events::events_catalog,
+      commands::sys::server_capacity,
+      commands::sys::connection_state,
+      commands::sys::read_only_set,
+      commands::sys::read_only_get,
+      commands::sys::reveal_in_file_manager,
+      commands::sys::port_check,
+      commands::sys::latency_histogram,
+      commands::sys::server_locality,
+      commands::sys::detect_ollama_env,
       commands::sys::detect_ollama,
       commands::sys::start_ollama_service,
       commands::sys::stop_ollama_service,
       commands::chat::chat_stream,
+      commands::chat::chat_compare,
       commands::chat::chat_cancel,
+      commands::chat::chat_send,
+      commands::generate::generate,
       commands::models::models_list,
+      commands::models::models_by_family,
+      commands::models::models_list_by_recency,
+      commands::models::disk_projection,
+      commands::models::pull_eta_estimate,
+      commands::models::models_search,
+      commands::models::models_export,
+      commands::models::models_list_with_status,
+      commands::models::vram_fit,
+      commands::models::models_list_detailed,
+      commands::models::model_smoke_test,
+      commands::models::model_prompt_eval_speed,
+      commands::models::model_update_available,
+      commands::models::model_quant_matrix,
       commands::models::model_pull,
       commands::models::model_pull_cancel,
+      commands::models::model_pull_cancel_all,
+      commands::models::model_pull_pause,
+      commands::models::model_pull_resume,
+      commands::models::pull_status,
+      commands::models::models_pull_manifest,
       commands::models::model_delete,
+      commands::models::models_delete_batch,
+      commands::models::model_unload,
+      commands::models::model_copy,
+      commands::models::model_create,
+      commands::models::model_create_cancel,
       commands::models::model_show,
+      commands::models::model_template_vars,
+      commands::modelfile::modelfile_of,
+      commands::modelfile::modelfile_clean,
+      commands::modelfile::modelfile_validate,
+      commands::license::model_license,
+      commands::embeddings::embeddings_create,
+      commands::embeddings::embeddings_cancel,
+      commands::embeddings::embedding_dimension,
       commands::settings::settings_get,
       commands::settings::settings_set,
+      commands::settings::settings_get_field,
+      commands::settings::params_merge,
+      commands::settings::config_doctor,
+      commands::settings::settings_validate,
+      commands::snapshot::state_snapshot,
+      commands::snapshot::state_restore,
+      commands::settings::theme_get,
+      commands::settings::theme_set,
+      commands::settings::theme_resolved,
       commands::db::db_create_chat,
       commands::db::db_append_message,
       commands::db::db_list_chats,
@@ -33,12 +87,21 @@ pub fn run() {
       commands::db::db_set_chat_model,
       commands::db::db_set_chat_title,
       commands::db::db_list_chats_with_flags,
+      commands::db::conversation_list,
+      commands::db::conversations_diff,
+      commands::db::conversation_import_openai,
+      commands::db::db_add_chat_tag,
+      commands::db::db_remove_chat_tag,
+      commands::db::db_list_chat_tags,
+      commands::db::conversations_search,
       commands::monitoring::start_system_monitoring,
       commands::monitoring::stop_system_monitoring,
       commands::monitoring::get_system_metrics,
       commands::monitoring::get_model_metrics,
       commands::monitoring::get_ollama_status,
       commands::monitoring::ollama_ps,
+      commands::monitoring::models_ps,
+      commands::monitoring::model_memory_sample,
       commands::monitoring::stop_model,
       commands::mcp::connect_mcp_server,
       commands::mcp::connect_mcp_http,
@@ -48,9 +111,24 @@ pub fn run() {
       commands::settings::provider_update,
       commands::settings::provider_delete,
       commands::settings::provider_set_active,
+      commands::settings::server_profile_add,
+      commands::settings::server_profile_remove,
+      commands::settings::server_profile_activate,
       commands::settings::provider_list,
       commands::settings::provider_get_active
     ])
+    .on_window_event(|window, event| match event {
+      // Re-resolve so a "system" theme follows the OS appearance live
+      tauri::WindowEvent::ThemeChanged(_) => {
+        let app = window.app_handle().clone();
+        tauri::async_runtime::spawn(async move {
+          commands::settings::emit_resolved_theme(&app).await;
+        });
+      }
+      // Minimizing also blurs the window, so focus alone gates background polling
+      tauri::WindowEvent::Focused(focused) => commands::sys::set_window_active(*focused),
+      _ => {}
+    })
     .setup(|app| {
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -61,6 +139,11 @@ pub fn run() {
       }
 
       app.manage(std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::<String, std::sync::Arc<std::sync::atomic::AtomicBool>>::new())));
+      app.manage(commands::models::PullStatusMap::default());
+      app.manage(commands::models::PullLimiter::default());
+      app.manage(commands::models::HttpClient::default());
+      app.manage(commands::sys::SharedConnectionState::default());
+      commands::sys::spawn_connection_monitor(app.handle().clone());
       Ok(())
     })
     .run(tauri::generate_context!())
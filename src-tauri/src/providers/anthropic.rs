@@ -332,6 +332,8 @@ impl AnthropicStream {
                          prompt_tokens: Some(self.input_tokens),
                          completion_tokens: Some(self.output_tokens),
                          total_tokens: Some(self.input_tokens + self.output_tokens),
+                         load_duration_ms: None,
+                         stats: None,
                      }));
                      // We don't need to emit Done explicitly as stream end implicitly does it, but we could.
                 }
@@ -306,6 +306,8 @@ impl GeminiStream {
                      prompt_tokens: usage.prompt_token_count,
                      completion_tokens: usage.candidates_token_count,
                      total_tokens: usage.total_token_count,
+                     load_duration_ms: None,
+                     stats: None,
                  }));
              }
         }
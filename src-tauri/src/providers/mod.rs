@@ -96,12 +96,61 @@ pub struct UsageStats {
     pub total_tokens: Option<i32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How long Ollama keeps a model loaded after a request: whole seconds (`-1` keeps it
+/// forever, `0` unloads immediately) or a Go-style duration string such as "5m" or "1h30m"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum KeepAlive {
+    Seconds(i64),
+    Duration(String),
+}
+
+impl KeepAlive {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            KeepAlive::Seconds(_) => Ok(()),
+            KeepAlive::Duration(s) if is_duration(s) => Ok(()),
+            KeepAlive::Duration(s) => Err(format!("Invalid keep_alive '{}', expected e.g. \"5m\", \"1h\" or -1", s)),
+        }
+    }
+}
+
+// Mirrors Go's time.ParseDuration, which Ollama uses: signed runs of <number><unit>
+fn is_duration(s: &str) -> bool {
+    let s = s.strip_prefix(['-', '+']).unwrap_or(s);
+    if s == "0" {
+        return true;
+    }
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        if !rest[..digits].chars().any(|c| c.is_ascii_digit()) {
+            return false;
+        }
+        rest = &rest[digits..];
+        let unit = ["ns", "us", "µs", "ms", "s", "m", "h"].iter().find(|u| rest.starts_with(*u));
+        match unit {
+            Some(u) => rest = &rest[u.len()..],
+            None => return false,
+        }
+    }
+    !s.is_empty()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChatOptions {
     pub temperature: Option<f64>,
     pub top_k: Option<i32>,
     pub top_p: Option<f64>,
     pub max_tokens: Option<i32>,
+    pub repeat_penalty: Option<f64>,
+    pub repeat_last_n: Option<i32>,
+    pub presence_penalty: Option<f64>,
+    pub frequency_penalty: Option<f64>,
+    /// Keep the model off the GPU (Ollama only)
+    pub force_cpu: Option<bool>,
+    /// Ollama only; the server default applies when unset
+    pub keep_alive: Option<KeepAlive>,
 }
 
 pub mod traits;
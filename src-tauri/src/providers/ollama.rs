@@ -7,7 +7,7 @@ use serde_json::json;
 use std::collections::VecDeque;
 use std::pin::Pin;
 use std::task::{Context, Poll};
-use crate::providers::traits::{LLMProvider, ProviderEvent, Usage};
+use crate::providers::traits::{GenerationStats, LLMProvider, ProviderEvent, Usage};
 use crate::providers::{ChatMessage, ProviderConfig, ChatOptions};
 
 #[derive(Debug, Deserialize, Clone)]
@@ -24,7 +24,11 @@ struct OllamaResponse {
     done: bool,
     prompt_eval_count: Option<i32>,
     eval_count: Option<i32>,
-    // timestamps...
+    /// Nanoseconds spent loading the model; near zero when it was already warm
+    load_duration: Option<u64>,
+    total_duration: Option<u64>,
+    prompt_eval_duration: Option<u64>,
+    eval_duration: Option<u64>,
 }
 
 pub struct OllamaProvider;
@@ -87,20 +91,10 @@ impl LLMProvider for OllamaProvider {
         payload["messages"] = json!(final_messages);
         
         if let Some(ref opts) = options {
-             let mut options_map = serde_json::Map::new();
-             if let Some(temp) = opts.temperature { 
-                 options_map.insert("temperature".to_string(), json!(temp)); 
+             payload["options"] = json!(ollama_options(opts));
+             if let Some(ref keep_alive) = opts.keep_alive {
+                 payload["keep_alive"] = json!(keep_alive);
              }
-             if let Some(top_k) = opts.top_k { 
-                 options_map.insert("top_k".to_string(), json!(top_k)); 
-             }
-             if let Some(top_p) = opts.top_p { 
-                 options_map.insert("top_p".to_string(), json!(top_p)); 
-             }
-             if let Some(max_tokens) = opts.max_tokens { 
-                 options_map.insert("num_predict".to_string(), json!(max_tokens)); 
-             }
-             payload["options"] = json!(options_map);
         }
 
         let response = client.post(&endpoint)
@@ -121,20 +115,10 @@ impl LLMProvider for OllamaProvider {
                 });
                 
                 if let Some(ref opts) = options {
-                    let mut options_map = serde_json::Map::new();
-                    if let Some(temp) = opts.temperature { 
-                        options_map.insert("temperature".to_string(), json!(temp)); 
-                    }
-                    if let Some(top_k) = opts.top_k { 
-                        options_map.insert("top_k".to_string(), json!(top_k)); 
-                    }
-                    if let Some(top_p) = opts.top_p { 
-                        options_map.insert("top_p".to_string(), json!(top_p)); 
+                    retry_payload["options"] = json!(ollama_options(opts));
+                    if let Some(ref keep_alive) = opts.keep_alive {
+                        retry_payload["keep_alive"] = json!(keep_alive);
                     }
-                    if let Some(max_tokens) = opts.max_tokens { 
-                        options_map.insert("num_predict".to_string(), json!(max_tokens)); 
-                    }
-                    retry_payload["options"] = json!(options_map);
                 }
                 
                 let retry_response = client.post(&endpoint)
@@ -161,6 +145,40 @@ impl LLMProvider for OllamaProvider {
     }
 }
 
+/// Map unified chat options onto Ollama's `options` object
+fn ollama_options(opts: &ChatOptions) -> serde_json::Map<String, serde_json::Value> {
+    let mut options_map = serde_json::Map::new();
+    if let Some(temp) = opts.temperature {
+        options_map.insert("temperature".to_string(), json!(temp));
+    }
+    if let Some(top_k) = opts.top_k {
+        options_map.insert("top_k".to_string(), json!(top_k));
+    }
+    if let Some(top_p) = opts.top_p {
+        options_map.insert("top_p".to_string(), json!(top_p));
+    }
+    if let Some(max_tokens) = opts.max_tokens {
+        options_map.insert("num_predict".to_string(), json!(max_tokens));
+    }
+    if let Some(repeat_penalty) = opts.repeat_penalty {
+        options_map.insert("repeat_penalty".to_string(), json!(repeat_penalty));
+    }
+    if let Some(repeat_last_n) = opts.repeat_last_n {
+        options_map.insert("repeat_last_n".to_string(), json!(repeat_last_n));
+    }
+    if let Some(presence_penalty) = opts.presence_penalty {
+        options_map.insert("presence_penalty".to_string(), json!(presence_penalty));
+    }
+    if let Some(frequency_penalty) = opts.frequency_penalty {
+        options_map.insert("frequency_penalty".to_string(), json!(frequency_penalty));
+    }
+    if opts.force_cpu == Some(true) {
+        // Zero offloaded layers runs the whole model on the CPU
+        options_map.insert("num_gpu".to_string(), json!(0));
+    }
+    options_map
+}
+
 struct OllamaStream {
     inner: Pin<Box<dyn Stream<Item = reqwest::Result<bytes::Bytes>> + Send>>,
     buffer: String,
@@ -211,6 +229,16 @@ impl OllamaStream {
                          prompt_tokens: chunk.prompt_eval_count,
                          completion_tokens: chunk.eval_count,
                          total_tokens: Some(chunk.prompt_eval_count.unwrap_or(0) + chunk.eval_count.unwrap_or(0)),
+                         load_duration_ms: chunk.load_duration.map(|ns| ns / 1_000_000),
+                         stats: Some(GenerationStats {
+                             total_duration: chunk.total_duration,
+                             load_duration: chunk.load_duration,
+                             prompt_eval_count: chunk.prompt_eval_count,
+                             prompt_eval_duration: chunk.prompt_eval_duration,
+                             eval_count: chunk.eval_count,
+                             eval_duration: chunk.eval_duration,
+                             tokens_per_sec: None,
+                         }.with_rate()),
                      };
                      self.queue.push_back(ProviderEvent::Usage(usage));
                 }
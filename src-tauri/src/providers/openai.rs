@@ -24,6 +24,10 @@ struct OpenAIRequest {
     max_tokens: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -130,12 +134,16 @@ impl LLMProvider for OpenAIProvider {
             temperature: None,
             max_tokens: None,
             top_p: None,
+            presence_penalty: None,
+            frequency_penalty: None,
         };
         
         if let Some(opts) = options {
             request_body.temperature = opts.temperature;
             request_body.max_tokens = opts.max_tokens;
             request_body.top_p = opts.top_p;
+            request_body.presence_penalty = opts.presence_penalty;
+            request_body.frequency_penalty = opts.frequency_penalty;
         }
 
         let client = reqwest::Client::new();
@@ -245,6 +253,8 @@ impl OpenAIStream {
                      prompt_tokens: usage.prompt_tokens,
                      completion_tokens: usage.completion_tokens,
                      total_tokens: usage.total_tokens,
+                     load_duration_ms: None,
+                     stats: None,
                  }));
              }
              
@@ -1,22 +1,40 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use futures::StreamExt;
 use serde_json::Value;
 
-use crate::providers::traits::{LLMProvider, ProviderEvent};
-use crate::providers::{ChatMessage, ProviderConfig, ChatOptions};
+use crate::providers::traits::{GenerationStats, LLMProvider, ProviderEvent};
+use crate::providers::{ChatMessage, ProviderConfig, ChatOptions, ProviderType};
 use crate::mcp::McpClient;
+use crate::commands::chat::graceful_cut;
+use crate::commands::models::HttpClient;
+use crate::commands::monitoring::{fetch_ps, is_same_model};
+use crate::commands::events;
 
 pub struct ChatOrchestrator {
     app: AppHandle,
     provider: Box<dyn LLMProvider + Send + Sync>,
+    max_response_chars: Option<usize>,
+    stop_group: Option<String>,
 }
 
 impl ChatOrchestrator {
     pub fn new(app: AppHandle, provider: Box<dyn LLMProvider + Send + Sync>) -> Self {
-        Self { app, provider }
+        Self { app, provider, max_response_chars: None, stop_group: None }
+    }
+
+    /// Stop generating once the assistant has produced this many characters in total
+    pub fn with_max_response_chars(mut self, limit: Option<usize>) -> Self {
+        self.max_response_chars = limit;
+        self
+    }
+
+    /// Also honour a graceful stop requested for `group`, e.g. the compare run this stream belongs to
+    pub fn with_stop_group(mut self, group: Option<String>) -> Self {
+        self.stop_group = group;
+        self
     }
 
     pub async fn run_conversation(
@@ -35,9 +53,18 @@ impl ChatOrchestrator {
         
         let mut loop_count = 0;
         const MAX_LOOPS: i32 = 10;
+        // Every tool call made across turns, reported with the final message
+        let mut executed_tool_calls: Vec<Value> = Vec::new();
+        // Reported by the first turn; a cold start shows up here rather than as slow tokens
+        let mut load_duration_ms: Option<u64> = None;
+        // Timings of the latest turn, which is the one that produced the final answer
+        let mut stats: Option<GenerationStats> = None;
+        // Characters produced across all turns, checked against `max_response_chars`
+        let mut response_chars = 0usize;
+        let mut done_reason = "stop";
         
         // Emit stream start event
-        let _ = self.app.emit("chat:stream-start", serde_json::json!({"stream_id": stream_id}));
+        let _ = self.app.emit(events::CHAT_STREAM_START, serde_json::json!({"stream_id": stream_id, "model": model}));
 
         loop {
             if loop_count >= MAX_LOOPS {
@@ -47,10 +74,16 @@ impl ChatOrchestrator {
             loop_count += 1;
             
             if should_cancel.load(Ordering::Relaxed) {
-                 let _ = self.app.emit("chat:cancelled", serde_json::json!({"stream_id": stream_id}));
+                 let _ = self.app.emit(events::CHAT_CANCELLED, serde_json::json!({"stream_id": stream_id, "model": model}));
                  return Ok(());
             }
 
+            // Until the first chunk arrives the model may still be loading into memory;
+            // the first `chat:chunk` marks the switch to generating
+            if loop_count == 1 {
+                let _ = self.app.emit(events::CHAT_LOADING, serde_json::json!({"stream_id": stream_id, "model": model}));
+            }
+
             // Start stream from provider
             let mut stream = self.provider.stream_chat(config, model, &messages, tools.clone(), options.clone()).await?;
             
@@ -63,47 +96,98 @@ impl ChatOrchestrator {
                  }
                  
                  match event {
-                     ProviderEvent::Content(s) => {
+                     ProviderEvent::Content(mut s) => {
+                         let mut over_limit = false;
+                         if let Some(limit) = self.max_response_chars {
+                             let len = s.chars().count();
+                             if response_chars + len > limit {
+                                 s = s.chars().take(limit - response_chars).collect();
+                                 over_limit = true;
+                             }
+                             response_chars += s.chars().count();
+                         }
+                         let graceful_stop = graceful_cut(stream_id, self.stop_group.as_deref(), &s);
+                         if let Some(cut) = graceful_stop {
+                             s.truncate(cut);
+                         }
                          full_content.push_str(&s);
                          // Emit chunk to frontend
-                         let _ = self.app.emit("chat:chunk", serde_json::json!({
+                         let _ = self.app.emit(events::CHAT_CHUNK, serde_json::json!({
                              "stream_id": stream_id,
+                             "model": model,
                              "message": { "role": "assistant", "content": s },
                              "done": false
                          }));
+                         // Dropping the stream aborts the request, so a looping model stops here
+                         if over_limit {
+                             done_reason = "max_length_guard";
+                             break;
+                         }
+                         if graceful_stop.is_some() {
+                             let _ = self.app.emit(events::CHAT_CANCELLED, serde_json::json!({"stream_id": stream_id, "model": model, "graceful": true}));
+                             return Ok(());
+                         }
                      },
                      ProviderEvent::ToolCall(tc) => {
                          tool_calls.push(tc);
                      },
                      ProviderEvent::Error(e) => {
-                          let _ = self.app.emit("chat:error", serde_json::json!({"stream_id": stream_id, "error": e}));
+                          let _ = self.app.emit(events::CHAT_ERROR, serde_json::json!({"stream_id": stream_id, "model": model, "error": e}));
                           return Err(anyhow::anyhow!(e));
                      },
-                     ProviderEvent::Usage(_) => {
-                         // Usage stats can be handled here if needed
+                     ProviderEvent::Usage(usage) => {
+                         if load_duration_ms.is_none() {
+                             load_duration_ms = usage.load_duration_ms;
+                         }
+                         if usage.stats.is_some() {
+                             stats = usage.stats;
+                         }
                      }
                  }
             }
             
             if should_cancel.load(Ordering::Relaxed) {
-                 let _ = self.app.emit("chat:cancelled", serde_json::json!({"stream_id": stream_id}));
+                 let _ = self.app.emit(events::CHAT_CANCELLED, serde_json::json!({"stream_id": stream_id, "model": model}));
                  return Ok(());
             }
 
-            // If no tool calls, we are done
-            if tool_calls.is_empty() {
+            // If no tool calls, we are done (a guarded response doesn't get to run tools)
+            if tool_calls.is_empty() || done_reason == "max_length_guard" {
                 // Emit final chunk with done=true
-                let _ = self.app.emit("chat:chunk", serde_json::json!({
+                let _ = self.app.emit(events::CHAT_CHUNK, serde_json::json!({
                      "stream_id": stream_id,
+                     "model": model,
                      "message": { "role": "assistant", "content": "" },
                      "done": true
                  }));
-                 let _ = self.app.emit("chat:complete", serde_json::json!({"stream_id": stream_id, "completed": true}));
+                 let ran_on_cpu = self.ran_on_cpu(config, model).await;
+                 // Carry the full message so persistence doesn't depend on every chunk arriving
+                 let _ = self.app.emit(events::CHAT_COMPLETE, serde_json::json!({
+                     "stream_id": stream_id,
+                     "model": model,
+                     "completed": true,
+                     "done_reason": done_reason,
+                     "ran_on_cpu": ran_on_cpu,
+                     "load_duration_ms": load_duration_ms,
+                     "message": {
+                         "role": "assistant",
+                         "content": full_content,
+                         "tool_calls": executed_tool_calls,
+                     }
+                 }));
+                 let _ = self.app.emit(events::CHAT_DONE, serde_json::json!({
+                     "stream_id": stream_id,
+                     "model": model,
+                     "done_reason": done_reason,
+                     "stats": stats,
+                 }));
                 break;
             }
             
             // Handle tool calls - This is the "Loop" part
             
+            executed_tool_calls.extend(tool_calls.iter().cloned());
+
             // 1. Append assistant message with content and tool_calls
             messages.push(ChatMessage {
                 role: "assistant".to_string(),
@@ -123,8 +207,9 @@ impl ChatOrchestrator {
                      let args = serde_json::from_str(args_str).unwrap_or(serde_json::json!({}));
 
                      // Notify frontend of tool execution
-                     let _ = self.app.emit("chat:tool-start", serde_json::json!({
+                     let _ = self.app.emit(events::CHAT_TOOL_START, serde_json::json!({
                          "stream_id": stream_id,
+                         "model": model,
                          "tool": name,
                          "args": args
                      }));
@@ -206,6 +291,16 @@ impl ChatOrchestrator {
         Ok(())
     }
     
+    /// Whether Ollama kept the model entirely off the GPU; None for other providers or if unknown
+    async fn ran_on_cpu(&self, config: &ProviderConfig, model: &str) -> Option<bool> {
+        if config.provider_type != ProviderType::Ollama {
+            return None;
+        }
+        let client = self.app.state::<HttpClient>().client();
+        let ps = fetch_ps(&client, &config.get_base_url()).await.ok()?;
+        ps.models.iter().find(|m| is_same_model(m, model)).map(|m| m.size_vram == 0)
+    }
+
     async fn gather_tools(&self) -> (Option<Vec<Value>>, HashMap<String, String>) {
         let mut available_tools = Vec::new();
         let mut tool_mapping = HashMap::new();
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use crate::providers::{ChatMessage, ProviderConfig, ChatOptions};
 
@@ -11,6 +12,33 @@ pub struct Usage {
     pub completion_tokens: Option<i32>,
     #[allow(dead_code)]
     pub total_tokens: Option<i32>,
+    /// Time the server spent loading the model before generating (Ollama only)
+    pub load_duration_ms: Option<u64>,
+    /// Full timing breakdown from the final chunk (Ollama only)
+    pub stats: Option<GenerationStats>,
+}
+
+/// Ollama's timings for one generation; durations are in nanoseconds as the server reports them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub total_duration: Option<u64>,
+    pub load_duration: Option<u64>,
+    pub prompt_eval_count: Option<i32>,
+    pub prompt_eval_duration: Option<u64>,
+    pub eval_count: Option<i32>,
+    pub eval_duration: Option<u64>,
+    pub tokens_per_sec: Option<f64>,
+}
+
+impl GenerationStats {
+    /// Fill in `tokens_per_sec` from the eval count and duration
+    pub fn with_rate(mut self) -> Self {
+        self.tokens_per_sec = match (self.eval_count, self.eval_duration) {
+            (Some(count), Some(ns)) if ns > 0 => Some(count as f64 / (ns as f64 / 1e9)),
+            _ => None,
+        };
+        self
+    }
 }
 
 #[derive(Debug, Clone)]